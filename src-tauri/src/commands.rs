@@ -4,13 +4,31 @@
 //! through Tauri's IPC mechanism. These commands provide the interface between
 //! the frontend and the system monitoring functionality.
 
-use crate::monitoring::{ProcessInfo, ProcessMonitor, SystemStats};
+use crate::monitoring::{
+    group_members, group_processes, read_cgroup_pressure, read_cpu_info, read_login_sessions,
+    read_process_cgroup_pressure, read_process_memory_details, read_process_numa_memory,
+    read_process_pss, read_recordings, read_sensors, read_system_info, user_name, CpuInfo,
+    EnvironmentDiff, ExitedProcess, LoginSession, MetricsRange, MountFilter, NetworkFilter,
+    PressureStats, ProcessCgroupPressure, ProcessEnvironment, ProcessEvent, ProcessGroup,
+    ProcessGroupDetails, ProcessGrouping, ProcessHandle, ProcessHistory, ProcessInfo, ProcessKey,
+    ProcessMemoryDetails, ProcessMonitor, ProcessNumaMemory, ProcessSignal, RecordedSample,
+    RecorderConfig, RecorderStatus, ReplayControl, ReplayStatus, Sensor, SystemInfo, SystemStats,
+};
 use crate::state::AppState;
-use sysinfo::SystemExt;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use sysinfo::{System, SystemExt};
 use tauri::State;
 
+/// How long `kill_process` waits for the killed process to exit
+const KILL_CONFIRM_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Retrieves the current list of processes and system statistics
 ///
+/// While a replay is open, the snapshot at the replay position is returned
+/// instead of live data.
+///
 /// # Arguments
 ///
 /// * `state` - The application state containing system monitoring components
@@ -26,41 +44,901 @@ use tauri::State;
 /// Returns an error string if:
 /// * Failed to acquire locks on system state
 /// * Failed to collect process information
+/// * Failed to read the replayed snapshot
 #[tauri::command]
 pub async fn get_processes(
     state: State<'_, AppState>,
 ) -> Result<(Vec<ProcessInfo>, SystemStats), String> {
-    let mut sys = state.sys.lock().map_err(|e| e.to_string())?;
-    sys.refresh_all();
-    sys.refresh_networks_list();
-    sys.refresh_disks_list();
+    if let Some(frame) = state
+        .capture
+        .lock()
+        .map_err(|e| e.to_string())?
+        .replay_frame()
+    {
+        return frame;
+    }
+    state.collect()
+}
 
-    let mut process_monitor = state.process_monitor.lock().map_err(|e| e.to_string())?;
-    let mut system_monitor = state.system_monitor.lock().map_err(|e| e.to_string())?;
+/// Starts capturing every collected snapshot to a file
+///
+/// # Arguments
+///
+/// * `path` - File to create; an existing file is overwritten
+/// * `state` - The application state
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire locks on the capture or system state
+/// * A replay is open
+/// * The file cannot be created
+#[tauri::command]
+pub async fn start_capture(path: String, state: State<'_, AppState>) -> Result<(), String> {
+    let hostname = state.sys.lock().map_err(|e| e.to_string())?.host_name();
+    let mut capture = state.capture.lock().map_err(|e| e.to_string())?;
+    capture.start_capture(Path::new(&path), hostname)
+}
+
+/// Stops the running capture
+///
+/// # Returns
+///
+/// The number of snapshots written
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire lock on the capture
+/// * No capture is running
+/// * Writing a snapshot failed, which ends the capture early
+#[tauri::command]
+pub async fn stop_capture(state: State<'_, AppState>) -> Result<usize, String> {
+    let mut capture = state.capture.lock().map_err(|e| e.to_string())?;
+    capture.stop_capture()
+}
+
+/// Opens a capture file and serves its snapshots through `get_processes`
+///
+/// The replay starts paused at the first snapshot.
+///
+/// # Arguments
+///
+/// * `path` - Capture file written by `start_capture`
+/// * `state` - The application state
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire lock on the capture
+/// * A capture is running
+/// * The file cannot be read, is not a capture or holds no snapshots
+#[tauri::command]
+pub async fn open_replay(path: String, state: State<'_, AppState>) -> Result<ReplayStatus, String> {
+    let mut capture = state.capture.lock().map_err(|e| e.to_string())?;
+    capture.open_replay(Path::new(&path))
+}
 
-    let processes = process_monitor.collect_processes(&sys)?;
-    let system_stats = system_monitor.collect_stats(&sys);
+/// Closes the replay so `get_processes` returns live data again
+///
+/// # Errors
+///
+/// Returns an error string if failed to acquire lock on the capture
+#[tauri::command]
+pub async fn close_replay(state: State<'_, AppState>) -> Result<(), String> {
+    let mut capture = state.capture.lock().map_err(|e| e.to_string())?;
+    capture.close_replay();
+    Ok(())
+}
 
-    Ok((processes, system_stats))
+/// Retrieves the playback state of the open replay
+///
+/// # Returns
+///
+/// The playback state, or `None` while showing live data
+///
+/// # Errors
+///
+/// Returns an error string if failed to acquire lock on the capture
+#[tauri::command]
+pub async fn get_replay_status(state: State<'_, AppState>) -> Result<Option<ReplayStatus>, String> {
+    let capture = state.capture.lock().map_err(|e| e.to_string())?;
+    Ok(capture.replay_status())
 }
 
-/// Attempts to kill a process with the specified PID
+/// Plays, pauses, seeks or changes the speed of the open replay
 ///
 /// # Arguments
 ///
-/// * `pid` - Process ID to kill
+/// * `control` - The playback action, e.g. `{ "action": "seek", "timestamp": 1700000000000 }`
+/// * `state` - The application state
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire lock on the capture
+/// * No replay is open
+/// * The speed is outside 0.1 to 100
+#[tauri::command]
+pub async fn control_replay(
+    control: ReplayControl,
+    state: State<'_, AppState>,
+) -> Result<ReplayStatus, String> {
+    let mut capture = state.capture.lock().map_err(|e| e.to_string())?;
+    capture.control_replay(control)
+}
+
+/// Retrieves the recorded system metrics for a time range
+///
+/// Metrics are sampled every second in the background, including while no
+/// window is open, and on every `get_processes` call. Recent history is
+/// kept at one second resolution and older history at one minute resolution;
+/// the finest resolution that covers `start` is returned. While a replay is
+/// open, the range is in capture time and covers the replayed frames up to
+/// an hour before the replay position.
+///
+/// # Arguments
+///
+/// * `start` - Start of the range in milliseconds since the Unix epoch
+/// * `end` - End of the range in milliseconds since the Unix epoch
+/// * `state` - The application state
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire locks on the capture or system monitor
+/// * A replayed snapshot cannot be read
+#[tauri::command]
+pub async fn get_metrics_history(
+    start: u64,
+    end: u64,
+    state: State<'_, AppState>,
+) -> Result<MetricsRange, String> {
+    if let Some(range) = state
+        .capture
+        .lock()
+        .map_err(|e| e.to_string())?
+        .replay_history(start, end)
+    {
+        return range;
+    }
+    let system_monitor = state.system_monitor.lock().map_err(|e| e.to_string())?;
+    Ok(system_monitor.history(start, end))
+}
+
+/// Retrieves the settings, storage location and health of the metrics recorder
+///
+/// # Errors
+///
+/// Returns an error string if failed to acquire lock on the recorder
+#[tauri::command]
+pub async fn get_recorder_status(state: State<'_, AppState>) -> Result<RecorderStatus, String> {
+    let recorder = state.recorder.lock().map_err(|e| e.to_string())?;
+    Ok(recorder.status())
+}
+
+/// Replaces and saves the settings of the metrics recorder
+///
+/// Recordings outside the new retention limits are deleted immediately.
+///
+/// # Arguments
+///
+/// * `config` - Whether to record, the sampling interval, the number of
+///   processes per sample and the retention limits
+/// * `state` - The application state
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire lock on the recorder
+/// * The sampling interval is zero
+/// * The settings cannot be saved
+/// * Old recordings cannot be deleted
+#[tauri::command]
+pub async fn set_recorder_config(
+    config: RecorderConfig,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let mut recorder = state.recorder.lock().map_err(|e| e.to_string())?;
+    recorder.set_config(config)
+}
+
+/// Retrieves the samples written by the metrics recorder for a time range
+///
+/// # Arguments
+///
+/// * `start` - Start of the range in milliseconds since the Unix epoch
+/// * `end` - End of the range in milliseconds since the Unix epoch
 /// * `state` - The application state
 ///
 /// # Returns
 ///
-/// * `true` if the process was successfully killed
-/// * `false` if the process couldn't be killed or wasn't found
+/// The samples ordered by time, thinned out evenly for long ranges
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire lock on the recorder
+/// * The recording directory is not known yet
+/// * A recording cannot be read
+#[tauri::command]
+pub async fn query_recorded_metrics(
+    start: u64,
+    end: u64,
+    state: State<'_, AppState>,
+) -> Result<Vec<RecordedSample>, String> {
+    // Files are read without holding the recorder, which collections need
+    let directory = state
+        .recorder
+        .lock()
+        .map_err(|e| e.to_string())?
+        .directory()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| "The recording directory is not available".to_string())?;
+    read_recordings(&directory, start, end)
+}
+
+/// Retrieves information about the host and its operating system
+///
+/// # Arguments
+///
+/// * `state` - The application state
+///
+/// # Returns
+///
+/// Kernel, distribution, hostname, boot time, architecture, virtualization,
+/// init system and process, thread and user counts
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire locks on application state
+/// * A replay is open
+#[tauri::command]
+pub async fn get_system_info(state: State<'_, AppState>) -> Result<SystemInfo, String> {
+    ensure_live_host(&state)?;
+    let sys = state.sys.lock().map_err(|e| e.to_string())?;
+    Ok(read_system_info(&sys))
+}
+
+/// Retrieves the processor model, topology, caches and feature flags
 ///
 /// # Errors
 ///
 /// Returns an error string if failed to acquire lock on system state
 #[tauri::command]
-pub async fn kill_process(pid: u32, state: State<'_, AppState>) -> Result<bool, String> {
+pub async fn get_cpu_info(state: State<'_, AppState>) -> Result<CpuInfo, String> {
     let sys = state.sys.lock().map_err(|e| e.to_string())?;
-    Ok(ProcessMonitor::kill_process(&sys, pid))
+    Ok(read_cpu_info(&sys))
+}
+
+/// Retrieves Pressure Stall Information for a single cgroup
+///
+/// # Arguments
+///
+/// * `cgroup` - Path relative to the cgroup v2 root, e.g. `/system.slice/docker.service`
+/// * `state` - The application state
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire lock on capture state
+/// * A replay is open
+/// * The path escapes the cgroup hierarchy
+/// * The cgroup does not exist or exposes no pressure files
+#[tauri::command]
+pub async fn get_cgroup_pressure(
+    cgroup: String,
+    state: State<'_, AppState>,
+) -> Result<PressureStats, String> {
+    ensure_live_host(&state)?;
+    read_cgroup_pressure(&cgroup)
+}
+
+/// Retrieves Pressure Stall Information for the cgroup a process belongs to
+///
+/// # Arguments
+///
+/// * `key` - Identity (PID and start time) of the process
+/// * `state` - The application state
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire lock on capture state
+/// * A replay is open
+/// * The process has exited or its PID now belongs to a different process
+/// * The process is not in a cgroup v2 hierarchy
+/// * The cgroup exposes no pressure files
+#[tauri::command]
+pub async fn get_process_cgroup_pressure(
+    key: ProcessKey,
+    state: State<'_, AppState>,
+) -> Result<ProcessCgroupPressure, String> {
+    ensure_live_host(&state)?;
+    read_process_cgroup_pressure(&mut scratch_system(), key)
+}
+
+/// Retrieves temperature, fan, voltage, current and power sensor readings
+///
+/// Platforms other than Linux report temperatures only.
+///
+/// # Arguments
+///
+/// * `state` - The application state
+///
+/// # Returns
+///
+/// Readings grouped by device, with the thresholds reported by the drivers
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire locks on application state
+/// * A replay is open
+#[tauri::command]
+pub async fn get_sensors(state: State<'_, AppState>) -> Result<Vec<Sensor>, String> {
+    ensure_live_host(&state)?;
+    #[cfg(target_os = "linux")]
+    let sensors = read_sensors();
+    // Elsewhere sensors are read through sysinfo
+    #[cfg(not(target_os = "linux"))]
+    let sensors = read_sensors(&mut *state.sys.lock().map_err(|e| e.to_string())?);
+    Ok(sensors)
+}
+
+/// Opens a handle to a process the user has selected
+///
+/// On Linux this pins a pidfd to the process, so later signals can never
+/// reach a different process that happens to reuse the PID.
+///
+/// # Arguments
+///
+/// * `key` - Identity (PID and start time) of the selected process
+/// * `state` - The application state
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire locks on application state
+/// * A replay is open
+/// * The process has exited or its PID now belongs to a different process
+#[tauri::command]
+pub async fn select_process(key: ProcessKey, state: State<'_, AppState>) -> Result<(), String> {
+    ensure_live(&state)?;
+    let handle = ProcessHandle::open(&mut scratch_system(), key)?;
+    let mut actions = state.process_actions.lock().map_err(|e| e.to_string())?;
+    actions.insert(handle);
+    Ok(())
+}
+
+/// Releases the handle opened by [`select_process`]
+///
+/// # Arguments
+///
+/// * `key` - Identity of the previously selected process
+/// * `state` - The application state
+///
+/// # Errors
+///
+/// Returns an error string if failed to acquire lock on the action layer
+#[tauri::command]
+pub async fn release_process(key: ProcessKey, state: State<'_, AppState>) -> Result<(), String> {
+    let mut actions = state.process_actions.lock().map_err(|e| e.to_string())?;
+    actions.release(&key);
+    Ok(())
+}
+
+/// Sends a signal to the process identified by `key`
+///
+/// # Arguments
+///
+/// * `key` - Identity (PID and start time) of the target process
+/// * `signal` - Signal to send
+/// * `state` - The application state
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire locks on application state
+/// * A replay is open
+/// * The process has exited or its PID now belongs to a different process
+/// * The signal could not be delivered
+#[tauri::command]
+pub async fn send_signal(
+    key: ProcessKey,
+    signal: ProcessSignal,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    ensure_live(&state)?;
+    let handle = process_handle(&state, key)?;
+    handle.send_signal(signal)
+}
+
+/// Attempts to kill the process identified by `key`
+///
+/// # Arguments
+///
+/// * `key` - Identity (PID and start time) of the process to kill
+/// * `state` - The application state
+///
+/// # Returns
+///
+/// * `true` if the process was killed and its exit was confirmed
+/// * `false` if the signal was sent but the process is still running
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire locks on application state
+/// * A replay is open
+/// * The process has exited or its PID now belongs to a different process
+/// * The signal could not be delivered
+#[tauri::command]
+pub async fn kill_process(key: ProcessKey, state: State<'_, AppState>) -> Result<bool, String> {
+    ensure_live(&state)?;
+    let handle = process_handle(&state, key)?;
+    handle.send_signal(ProcessSignal::Kill)?;
+    let exited =
+        tauri::async_runtime::spawn_blocking(move || handle.wait_for_exit(KILL_CONFIRM_TIMEOUT))
+            .await
+            .map_err(|e| e.to_string())?;
+    if exited {
+        let mut actions = state.process_actions.lock().map_err(|e| e.to_string())?;
+        actions.release(&key);
+    }
+    Ok(exited)
+}
+
+/// Refuses actions on live processes while a replay is shown in their place
+fn ensure_live(state: &State<'_, AppState>) -> Result<(), String> {
+    if replay_open(state)? {
+        return Err("Processes cannot be changed while a replay is open".to_string());
+    }
+    Ok(())
+}
+
+/// Refuses reads of the live host that a replay has no record of
+///
+/// Without this, details of the host as it is now would be shown next to the
+/// replayed process list as if they belonged to it.
+fn ensure_live_host(state: &State<'_, AppState>) -> Result<(), String> {
+    if replay_open(state)? {
+        return Err("Not available in replay: this reads the live host".to_string());
+    }
+    Ok(())
+}
+
+/// Checks whether a replay is shown in place of the live host
+fn replay_open(state: &State<'_, AppState>) -> Result<bool, String> {
+    let capture = state.capture.lock().map_err(|e| e.to_string())?;
+    Ok(capture.replay_status().is_some())
+}
+
+/// Runs `view` on the process monitor of the open replay, or on the live one
+///
+/// Views derived from the process list go through here, so they match the
+/// replayed table while a replay is open.
+fn with_process_monitor<T>(
+    state: &State<'_, AppState>,
+    view: impl FnOnce(&ProcessMonitor) -> T,
+) -> Result<T, String> {
+    let mut capture = state.capture.lock().map_err(|e| e.to_string())?;
+    if let Some(monitor) = capture.replay_monitor() {
+        return monitor.map(view);
+    }
+    drop(capture);
+    let process_monitor = state.process_monitor.lock().map_err(|e| e.to_string())?;
+    Ok(view(&process_monitor))
+}
+
+/// Creates a `System` for looking up single processes on demand
+///
+/// Refreshing a process in the shared `System` restarts the interval sysinfo
+/// measures its CPU usage over, so the next sample would show a spike or
+/// zero for the inspected process.
+fn scratch_system() -> System {
+    System::new()
+}
+
+/// Returns the handle of a selected process, or opens a temporary one
+fn process_handle(
+    state: &State<'_, AppState>,
+    key: ProcessKey,
+) -> Result<Arc<ProcessHandle>, String> {
+    let selected = state
+        .process_actions
+        .lock()
+        .map_err(|e| e.to_string())?
+        .handle(&key);
+    match selected {
+        Some(handle) => Ok(handle),
+        None => ProcessHandle::open(&mut scratch_system(), key).map(Arc::new),
+    }
+}
+
+/// Retrieves recent process lifecycle events
+///
+/// # Arguments
+///
+/// * `limit` - Maximum number of events to return (defaults to 200)
+/// * `state` - The application state
+///
+/// # Returns
+///
+/// Recent fork, exec and exit events, oldest first
+///
+/// # Errors
+///
+/// Returns an error string if failed to acquire lock on the lifecycle monitor
+#[tauri::command]
+pub async fn get_process_events(
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ProcessEvent>, String> {
+    let lifecycle_monitor = state.lifecycle_monitor.lock().map_err(|e| e.to_string())?;
+    Ok(lifecycle_monitor.recent_events(limit.unwrap_or(200)))
+}
+
+/// Retrieves processes that exited recently
+///
+/// # Arguments
+///
+/// * `state` - The application state
+///
+/// # Returns
+///
+/// Recently exited processes with their last known and peak statistics,
+/// most recent first. Exit codes are only present when lifecycle events
+/// were delivered by the netlink connector.
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire locks on the capture or process monitor
+/// * A replayed snapshot cannot be read
+#[tauri::command]
+pub async fn get_exited_processes(
+    state: State<'_, AppState>,
+) -> Result<Vec<ExitedProcess>, String> {
+    // Captures hold no lifecycle events, so replayed exits have no exit codes
+    if let Some(monitor) = state
+        .capture
+        .lock()
+        .map_err(|e| e.to_string())?
+        .replay_monitor()
+    {
+        return monitor.map(|monitor| monitor.exited_processes(&[]));
+    }
+    let process_monitor = state.process_monitor.lock().map_err(|e| e.to_string())?;
+    let lifecycle_monitor = state.lifecycle_monitor.lock().map_err(|e| e.to_string())?;
+    let events = lifecycle_monitor.recent_events(usize::MAX);
+    Ok(process_monitor.exited_processes(&events))
+}
+
+/// Retrieves the recent CPU, memory and disk I/O history of a process
+///
+/// # Arguments
+///
+/// * `key` - Identity (PID and start time) of the process
+/// * `state` - The application state
+///
+/// # Returns
+///
+/// Up to an hour of samples at ten second resolution. Histories of exited
+/// processes are kept for five minutes.
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire locks on the capture or process monitor
+/// * A replayed snapshot cannot be read
+/// * The process was never seen or exited too long ago
+#[tauri::command]
+pub async fn get_process_history(
+    key: ProcessKey,
+    state: State<'_, AppState>,
+) -> Result<ProcessHistory, String> {
+    with_process_monitor(&state, |monitor| monitor.process_history(key))?
+        .ok_or_else(|| format!("No history recorded for process {}", key.pid))
+}
+
+/// Retrieves the logged in users and the processes of their sessions
+///
+/// # Arguments
+///
+/// * `state` - The application state
+///
+/// # Returns
+///
+/// One entry per login from utmp with terminal, remote host, login time and
+/// the summed CPU and memory usage of its processes, based on the last
+/// collected process list or the replayed one while a replay is open
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire locks on the capture or process monitor
+/// * A replayed snapshot cannot be read
+#[tauri::command]
+pub async fn get_sessions(state: State<'_, AppState>) -> Result<Vec<LoginSession>, String> {
+    with_process_monitor(&state, |monitor| {
+        read_login_sessions(monitor.last_processes())
+    })
+}
+
+/// Aggregates the process list by a shared property
+///
+/// # Arguments
+///
+/// * `grouping` - Property by which processes are grouped
+/// * `state` - The application state
+///
+/// # Returns
+///
+/// Groups with their instance count, summed resource usage and members,
+/// based on the last collected process list or the replayed one while a
+/// replay is open
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire locks on the capture or process monitor
+/// * A replayed snapshot cannot be read
+#[tauri::command]
+pub async fn get_process_groups(
+    grouping: ProcessGrouping,
+    state: State<'_, AppState>,
+) -> Result<Vec<ProcessGroup>, String> {
+    // User lookups may reach a directory service, so the lock is not held
+    let processes = with_process_monitor(&state, |monitor| monitor.last_processes().to_vec())?;
+    Ok(group_processes(&processes, grouping, user_name))
+}
+
+/// Retrieves the processes belonging to one group of `get_process_groups`
+///
+/// # Arguments
+///
+/// * `grouping` - Property by which processes are grouped
+/// * `key` - Value of the property shared by the group
+/// * `state` - The application state
+///
+/// # Returns
+///
+/// The members of the group, busiest first, with their summed PSS. PSS is
+/// read from each member's memory map on every call.
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire locks on the capture or process monitor
+/// * A replayed snapshot cannot be read
+#[tauri::command]
+pub async fn get_process_group_members(
+    grouping: ProcessGrouping,
+    key: String,
+    state: State<'_, AppState>,
+) -> Result<ProcessGroupDetails, String> {
+    let replaying = state
+        .capture
+        .lock()
+        .map_err(|e| e.to_string())?
+        .replay_status()
+        .is_some();
+    // Memory maps are read after the lock is released
+    let processes = with_process_monitor(&state, |monitor| monitor.last_processes().to_vec())?;
+    // Replayed processes are not running here, so their maps are not read
+    let read_pss = |key| (!replaying).then(|| read_process_pss(key)).flatten();
+    Ok(group_members(
+        &processes, grouping, &key, user_name, read_pss,
+    ))
+}
+
+/// Retrieves the environment variables of a single process
+///
+/// # Arguments
+///
+/// * `key` - Identity (PID and start time) of the process
+/// * `state` - The application state
+///
+/// # Returns
+///
+/// Parsed variables sorted by name, with values of secret-looking variables masked
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire locks on application state
+/// * A replay is open
+/// * The process has exited or its PID now belongs to a different process
+#[tauri::command]
+pub async fn get_process_environment(
+    key: ProcessKey,
+    state: State<'_, AppState>,
+) -> Result<ProcessEnvironment, String> {
+    ensure_live_host(&state)?;
+    let mut sys = scratch_system();
+    let inspector = state
+        .environment_inspector
+        .lock()
+        .map_err(|e| e.to_string())?;
+    inspector.environment(&mut sys, key)
+}
+
+/// Retrieves detailed memory accounting for a process, including PSS and huge pages
+///
+/// # Arguments
+///
+/// * `key` - Identity (PID and start time) of the process
+/// * `state` - The application state
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire lock on capture state
+/// * A replay is open
+/// * The process has exited or its PID now belongs to a different process
+/// * The memory map of the process cannot be read
+#[tauri::command]
+pub async fn get_process_memory_details(
+    key: ProcessKey,
+    state: State<'_, AppState>,
+) -> Result<ProcessMemoryDetails, String> {
+    ensure_live_host(&state)?;
+    read_process_memory_details(&mut scratch_system(), key)
+}
+
+/// Retrieves how a process's memory is distributed across NUMA nodes
+///
+/// # Arguments
+///
+/// * `key` - Identity (PID and start time) of the process
+/// * `state` - The application state
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire lock on capture state
+/// * A replay is open
+/// * The process has exited or its PID now belongs to a different process
+/// * The NUMA maps of the process cannot be read
+#[tauri::command]
+pub async fn get_process_numa_memory(
+    key: ProcessKey,
+    state: State<'_, AppState>,
+) -> Result<ProcessNumaMemory, String> {
+    ensure_live_host(&state)?;
+    read_process_numa_memory(&mut scratch_system(), key)
+}
+
+/// Compares the environment variables of two processes
+///
+/// # Arguments
+///
+/// * `left` - Identity of the first process
+/// * `right` - Identity of the second process
+/// * `state` - The application state
+///
+/// # Errors
+///
+/// Returns an error string if:
+/// * Failed to acquire locks on application state
+/// * A replay is open
+/// * Either process has exited or its PID now belongs to a different process
+#[tauri::command]
+pub async fn diff_process_environments(
+    left: ProcessKey,
+    right: ProcessKey,
+    state: State<'_, AppState>,
+) -> Result<EnvironmentDiff, String> {
+    ensure_live_host(&state)?;
+    let mut sys = scratch_system();
+    let inspector = state
+        .environment_inspector
+        .lock()
+        .map_err(|e| e.to_string())?;
+    inspector.diff(&mut sys, left, right)
+}
+
+/// Retrieves the patterns used to mask secret environment variables
+///
+/// # Errors
+///
+/// Returns an error string if failed to acquire lock on the inspector
+#[tauri::command]
+pub async fn get_secret_patterns(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let inspector = state
+        .environment_inspector
+        .lock()
+        .map_err(|e| e.to_string())?;
+    Ok(inspector.secret_patterns().to_vec())
+}
+
+/// Replaces the patterns used to mask secret environment variables
+///
+/// # Arguments
+///
+/// * `patterns` - Case-insensitive variable name globs, e.g. `*TOKEN*`
+/// * `state` - The application state
+///
+/// # Errors
+///
+/// Returns an error string if failed to acquire lock on the inspector or the
+/// patterns could not be saved
+#[tauri::command]
+pub async fn set_secret_patterns(
+    patterns: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let mut inspector = state
+        .environment_inspector
+        .lock()
+        .map_err(|e| e.to_string())?;
+    inspector.set_secret_patterns(patterns)
+}
+
+/// Retrieves the filter selecting which network interfaces are shown
+///
+/// # Errors
+///
+/// Returns an error string if failed to acquire lock on the system monitor
+#[tauri::command]
+pub async fn get_network_filter(state: State<'_, AppState>) -> Result<NetworkFilter, String> {
+    let system_monitor = state.system_monitor.lock().map_err(|e| e.to_string())?;
+    Ok(system_monitor.network_filter().clone())
+}
+
+/// Replaces the filter selecting which network interfaces are shown
+///
+/// The filter affects the interface list and the `filtered_network_*`
+/// totals only; `network_rx_bytes` and `network_tx_bytes` always cover every
+/// interface.
+///
+/// # Arguments
+///
+/// * `filter` - Include/exclude name globs, e.g. `docker*`, and the virtual interface toggle
+/// * `state` - The application state
+///
+/// # Errors
+///
+/// Returns an error string if failed to acquire lock on the system monitor
+#[tauri::command]
+pub async fn set_network_filter(
+    filter: NetworkFilter,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let mut system_monitor = state.system_monitor.lock().map_err(|e| e.to_string())?;
+    system_monitor.set_network_filter(filter);
+    Ok(())
+}
+
+/// Retrieves the filter selecting which mounted filesystems are shown
+///
+/// # Errors
+///
+/// Returns an error string if failed to acquire lock on the system monitor
+#[tauri::command]
+pub async fn get_mount_filter(state: State<'_, AppState>) -> Result<MountFilter, String> {
+    let system_monitor = state.system_monitor.lock().map_err(|e| e.to_string())?;
+    Ok(system_monitor.mount_filter().clone())
+}
+
+/// Replaces the filter selecting which mounted filesystems are shown
+///
+/// # Arguments
+///
+/// * `filter` - Filesystem type and mount point globs to hide, e.g. `tmpfs` or `/snap/*`
+/// * `state` - The application state
+///
+/// # Errors
+///
+/// Returns an error string if failed to acquire lock on the system monitor
+#[tauri::command]
+pub async fn set_mount_filter(
+    filter: MountFilter,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let mut system_monitor = state.system_monitor.lock().map_err(|e| e.to_string())?;
+    system_monitor.set_mount_filter(filter);
+    Ok(())
 }
@@ -10,7 +10,7 @@ mod state;
 mod ui;
 
 use state::AppState;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 
 /// Main entry point for the application
 ///
@@ -25,6 +25,40 @@ fn main() {
         .setup(|app| {
             let window = app.get_webview_window("main").unwrap();
             ui::setup_window_effects(&window).expect("Failed to apply window effects");
+
+            let data_dir = app.path().app_data_dir()?;
+            {
+                let state = app.state::<AppState>();
+                let mut recorder = state
+                    .recorder
+                    .lock()
+                    .expect("Failed to lock metrics recorder");
+                recorder.set_directory(data_dir.join("recordings"));
+                recorder.load_settings(data_dir.join("recorder.json"));
+            }
+            app.state::<AppState>()
+                .environment_inspector
+                .lock()
+                .expect("Failed to lock environment inspector")
+                .load_settings(data_dir.join("secret_patterns.json"));
+
+            let handle = app.handle().clone();
+            app.state::<AppState>()
+                .lifecycle_monitor
+                .lock()
+                .expect("Failed to lock lifecycle monitor")
+                .start(move |events| {
+                    let _ = handle.emit("process-activity", events);
+                });
+
+            // Keeps the metrics history and recorder filling while no window polls
+            let handle = app.handle().clone();
+            std::thread::Builder::new()
+                .name("sampler".into())
+                .spawn(move || loop {
+                    std::thread::sleep(state::SAMPLE_INTERVAL);
+                    let _ = handle.state::<AppState>().collect();
+                })?;
             Ok(())
         })
         .plugin(tauri_plugin_shell::init())
@@ -32,7 +66,41 @@ fn main() {
         .manage(AppState::new())
         .invoke_handler(tauri::generate_handler![
             commands::get_processes,
+            commands::start_capture,
+            commands::stop_capture,
+            commands::open_replay,
+            commands::close_replay,
+            commands::get_replay_status,
+            commands::control_replay,
+            commands::get_metrics_history,
+            commands::get_recorder_status,
+            commands::set_recorder_config,
+            commands::query_recorded_metrics,
+            commands::get_system_info,
+            commands::get_cpu_info,
+            commands::get_cgroup_pressure,
+            commands::get_process_cgroup_pressure,
+            commands::get_sensors,
             commands::kill_process,
+            commands::select_process,
+            commands::release_process,
+            commands::send_signal,
+            commands::get_process_events,
+            commands::get_exited_processes,
+            commands::get_process_history,
+            commands::get_sessions,
+            commands::get_process_groups,
+            commands::get_process_group_members,
+            commands::get_process_environment,
+            commands::diff_process_environments,
+            commands::get_process_memory_details,
+            commands::get_process_numa_memory,
+            commands::get_secret_patterns,
+            commands::set_secret_patterns,
+            commands::get_network_filter,
+            commands::set_network_filter,
+            commands::get_mount_filter,
+            commands::set_mount_filter,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
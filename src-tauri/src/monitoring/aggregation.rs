@@ -0,0 +1,223 @@
+//! Process list aggregation
+//!
+//! The process table lists every process separately, which hides who or what
+//! is using the machine when the load is spread over many processes. This
+//! module groups a process list by a shared property and sums the resource
+//! usage of each group. Network usage is not included: neither sysinfo nor
+//! procfs account network traffic per process.
+
+use super::{ProcessGroup, ProcessGroupDetails, ProcessGrouping, ProcessInfo, ProcessKey};
+use std::collections::HashMap;
+
+/// Groups processes and sums their resource usage
+///
+/// # Arguments
+///
+/// * `processes` - The process list to aggregate
+/// * `grouping` - Property by which processes are grouped
+/// * `user_name` - Resolves a user ID to a login name, if it has one
+///
+/// # Returns
+///
+/// The groups, ordered by CPU usage and then memory usage, highest first
+pub fn group_processes(
+    processes: &[ProcessInfo],
+    grouping: ProcessGrouping,
+    user_name: impl Fn(&str) -> Option<String>,
+) -> Vec<ProcessGroup> {
+    let mut members: HashMap<String, Vec<&ProcessInfo>> = HashMap::new();
+    for (key, process) in group_keys(processes, grouping, user_name) {
+        members.entry(key).or_default().push(process);
+    }
+
+    let mut groups: Vec<ProcessGroup> = members
+        .into_iter()
+        .map(|(key, mut processes)| {
+            processes.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+            ProcessGroup {
+                key,
+                process_count: processes.len(),
+                cpu_usage: processes.iter().map(|process| process.cpu_usage).sum(),
+                memory_usage: processes.iter().map(|process| process.memory_usage).sum(),
+                disk_read_bytes: processes.iter().map(|process| process.disk_usage.0).sum(),
+                disk_written_bytes: processes.iter().map(|process| process.disk_usage.1).sum(),
+                members: processes.iter().map(|process| process.key()).collect(),
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| {
+        b.cpu_usage
+            .total_cmp(&a.cpu_usage)
+            .then(b.memory_usage.cmp(&a.memory_usage))
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    groups
+}
+
+/// Returns the members of a single group with their summed PSS
+///
+/// PSS is only read here, for the group being drilled into, because reading
+/// a memory map is far more expensive than the rest of the aggregation.
+///
+/// # Arguments
+///
+/// * `processes` - The process list to search
+/// * `grouping` - Property by which processes are grouped
+/// * `key` - Value of the property shared by the group
+/// * `user_name` - Resolves a user ID to a login name, if it has one
+/// * `read_pss` - Reads the proportional set size of a process, if available
+///
+/// # Returns
+///
+/// The members, busiest first, and the PSS of those whose map was readable
+pub fn group_members(
+    processes: &[ProcessInfo],
+    grouping: ProcessGrouping,
+    key: &str,
+    user_name: impl Fn(&str) -> Option<String>,
+    read_pss: impl Fn(ProcessKey) -> Option<u64>,
+) -> ProcessGroupDetails {
+    let mut members: Vec<ProcessInfo> = group_keys(processes, grouping, user_name)
+        .filter(|(group, _)| group == key)
+        .map(|(_, process)| process.clone())
+        .collect();
+    members.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+
+    let mut pss = 0;
+    let mut pss_skipped = 0;
+    for member in &members {
+        match read_pss(member.key()) {
+            Some(bytes) => pss += bytes,
+            None => pss_skipped += 1,
+        }
+    }
+    ProcessGroupDetails {
+        members,
+        pss,
+        pss_skipped,
+    }
+}
+
+/// Pairs each process with the value of its grouping property
+///
+/// Processes carry numeric user IDs; each distinct ID is resolved to a login
+/// name once, and IDs without a user entry keep their number.
+fn group_keys<'a>(
+    processes: &'a [ProcessInfo],
+    grouping: ProcessGrouping,
+    user_name: impl Fn(&str) -> Option<String>,
+) -> impl Iterator<Item = (String, &'a ProcessInfo)> {
+    let mut user_names: HashMap<&'a str, String> = HashMap::new();
+    processes.iter().map(move |process| {
+        let key = match grouping {
+            ProcessGrouping::User => user_names
+                .entry(process.user.as_str())
+                .or_insert_with(|| user_name(&process.user).unwrap_or_else(|| process.user.clone()))
+                .clone(),
+            _ => group_key(process, grouping),
+        };
+        (key, process)
+    })
+}
+
+/// Returns the value of a grouping property that needs no lookup
+fn group_key(process: &ProcessInfo, grouping: ProcessGrouping) -> String {
+    match grouping {
+        ProcessGrouping::User => process.user.clone(),
+        ProcessGrouping::Name => process.name.clone(),
+        // Kernel threads and processes of other users may hide their executable
+        ProcessGrouping::Executable if process.executable.is_empty() => process.name.clone(),
+        ProcessGrouping::Executable => process.executable.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, user: &str, cpu_usage: f32, memory_usage: u64) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            ppid: 1,
+            name: "worker".to_string(),
+            executable: "/usr/bin/worker".to_string(),
+            cpu_usage,
+            memory_usage,
+            status: "Running".to_string(),
+            user: user.to_string(),
+            command: "worker --serve".to_string(),
+            threads: None,
+            root: "/".to_string(),
+            virtual_memory: 0,
+            start_time: 1_700_000_000,
+            run_time: 60,
+            disk_usage: (1000, 500),
+            session_id: None,
+        }
+    }
+
+    /// Tests grouping processes by user
+    #[test]
+    fn test_group_by_user() {
+        let processes = [
+            process(10, "1000", 5.0, 100),
+            process(11, "1001", 80.0, 50),
+            process(12, "1000", 20.0, 300),
+        ];
+        let user_name = |uid: &str| (uid == "1000").then(|| "alice".to_string());
+        let groups = group_processes(&processes, ProcessGrouping::User, user_name);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].key, "1001");
+        assert_eq!(groups[1].key, "alice");
+        assert_eq!(groups[1].process_count, 2);
+        assert_eq!(groups[1].cpu_usage, 25.0);
+        assert_eq!(groups[1].memory_usage, 400);
+        assert_eq!(groups[1].disk_read_bytes, 2000);
+        assert_eq!(groups[1].members[0].pid, 12);
+
+        let details = group_members(
+            &processes,
+            ProcessGrouping::User,
+            "alice",
+            user_name,
+            |_| None,
+        );
+        assert_eq!(details.members.len(), 2);
+        assert_eq!(details.pss_skipped, 2);
+    }
+
+    /// Tests grouping by executable, PSS sums and drilling down into a group
+    #[test]
+    fn test_group_by_executable() {
+        let mut processes = vec![
+            process(10, "1000", 5.0, 100),
+            process(11, "1000", 1.0, 100),
+            process(12, "1000", 2.0, 100),
+        ];
+        processes[1].name = "chrome".to_string();
+        processes[1].executable = "/opt/google/chrome/chrome".to_string();
+        processes[2].executable = String::new();
+
+        let groups = group_processes(&processes, ProcessGrouping::Executable, |_| None);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].key, "/usr/bin/worker");
+        assert_eq!(groups[1].key, "worker");
+
+        let by_name = group_processes(&processes, ProcessGrouping::Name, |_| None);
+        assert_eq!(by_name[0].key, "worker");
+        assert_eq!(by_name[0].process_count, 2);
+
+        // An unreadable member is skipped instead of hiding the whole sum
+        let details = group_members(
+            &processes,
+            ProcessGrouping::Name,
+            "worker",
+            |_| None,
+            |key| (key.pid != 12).then_some(u64::from(key.pid)),
+        );
+        assert_eq!(details.members.len(), 2);
+        assert_eq!(details.members[0].pid, 10);
+        assert_eq!(details.pss, 10);
+        assert_eq!(details.pss_skipped, 1);
+    }
+}
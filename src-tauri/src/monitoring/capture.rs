@@ -0,0 +1,602 @@
+//! Session capture and replay
+//!
+//! A capture stores every collected snapshot in a JSON Lines file: a header
+//! line followed by one frame per snapshot. A replay indexes the frames of
+//! such a file and serves them in place of live data, following a clock that
+//! can be paused, moved and sped up. Process histories, exited processes and
+//! the metrics history are rebuilt from the frames up to the replay position.
+
+use super::history::MetricsHistory;
+use super::{
+    MetricsRange, MetricsSample, ProcessInfo, ProcessMonitor, ReplayControl, ReplayStatus,
+    SystemStats,
+};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Value of the `format` field identifying a capture file
+const CAPTURE_FORMAT: &str = "neohtop-capture";
+
+/// Version of the capture file layout
+const CAPTURE_VERSION: u32 = 1;
+
+/// Slowest and fastest supported playback speeds
+const SPEED_RANGE: (f64, f64) = (0.1, 100.0);
+
+/// How far before the replay position frames are fed into the rebuilt
+/// views, in milliseconds; matches the span of a process history
+const VIEW_WINDOW_MS: u64 = 3_600_000;
+
+/// First line of a capture file
+#[derive(Serialize, Deserialize, Debug)]
+struct CaptureHeader {
+    /// Always `neohtop-capture`
+    format: String,
+    /// Layout version of the file
+    version: u32,
+    /// Host the capture was taken on
+    hostname: Option<String>,
+}
+
+/// One snapshot of a capture, as written
+#[derive(Serialize)]
+struct CaptureFrameRef<'a> {
+    /// Time of the snapshot in milliseconds since the Unix epoch
+    timestamp: u64,
+    /// Process list at the time of the snapshot
+    processes: &'a [ProcessInfo],
+    /// System statistics at the time of the snapshot
+    stats: &'a SystemStats,
+}
+
+/// One snapshot of a capture, as read back; its timestamp is in the index
+#[derive(Deserialize, Debug)]
+struct CaptureFrame {
+    /// Process list at the time of the snapshot
+    processes: Vec<ProcessInfo>,
+    /// System statistics at the time of the snapshot
+    stats: SystemStats,
+}
+
+/// Timestamp of a frame, read without decoding the rest of the line
+#[derive(Deserialize)]
+struct FrameTimestamp {
+    timestamp: u64,
+}
+
+/// Records live snapshots and replays captured ones
+#[derive(Debug, Default)]
+pub struct SessionCapture {
+    /// Capture being written, if any
+    writer: Option<CaptureWriter>,
+    /// Capture being replayed, if any
+    replay: Option<Replay>,
+    /// Write error that ended the last capture early
+    capture_error: Option<String>,
+}
+
+impl SessionCapture {
+    /// Creates an instance that neither records nor replays
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts writing live snapshots to a new capture file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - File to create; an existing file is overwritten
+    /// * `hostname` - Host name stored in the header
+    ///
+    /// # Errors
+    ///
+    /// Returns an error string if a replay is open or the file cannot be created
+    pub fn start_capture(&mut self, path: &Path, hostname: Option<String>) -> Result<(), String> {
+        if self.replay.is_some() {
+            return Err("Cannot capture while a replay is open".to_string());
+        }
+        self.writer = Some(CaptureWriter::create(path, hostname)?);
+        self.capture_error = None;
+        Ok(())
+    }
+
+    /// Stops the current capture
+    ///
+    /// # Returns
+    ///
+    /// The number of frames written, or an error string if no capture was
+    /// running, a write failed, or the file could not be flushed
+    pub fn stop_capture(&mut self) -> Result<usize, String> {
+        if let Some(error) = self.capture_error.take() {
+            return Err(format!("The capture stopped early: {}", error));
+        }
+        let writer = self
+            .writer
+            .take()
+            .ok_or_else(|| "No capture is running".to_string())?;
+        writer.finish()
+    }
+
+    /// Appends a live snapshot to the running capture, if any
+    ///
+    /// A failed write ends the capture, leaving the file readable up to the
+    /// last good frame; the error is reported by `stop_capture`.
+    pub fn write_frame(&mut self, processes: &[ProcessInfo], stats: &SystemStats) {
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+        if let Err(error) = writer.write(processes, stats) {
+            self.writer = None;
+            self.capture_error = Some(error);
+        }
+    }
+
+    /// Opens a capture file for replay, paused at its first frame
+    ///
+    /// # Errors
+    ///
+    /// Returns an error string if a capture is running, or the file cannot be
+    /// read, is not a capture, or holds no frames
+    pub fn open_replay(&mut self, path: &Path) -> Result<ReplayStatus, String> {
+        if self.writer.is_some() {
+            return Err("Cannot replay while a capture is running".to_string());
+        }
+        let replay = Replay::open(path)?;
+        let status = replay.status();
+        self.replay = Some(replay);
+        Ok(status)
+    }
+
+    /// Closes the replay and returns to live data
+    pub fn close_replay(&mut self) {
+        self.replay = None;
+    }
+
+    /// Returns the state of the replay, if one is open
+    pub fn replay_status(&self) -> Option<ReplayStatus> {
+        self.replay.as_ref().map(Replay::status)
+    }
+
+    /// Applies a playback control to the open replay
+    ///
+    /// # Errors
+    ///
+    /// Returns an error string if no replay is open or the speed is out of range
+    pub fn control_replay(&mut self, control: ReplayControl) -> Result<ReplayStatus, String> {
+        let replay = self
+            .replay
+            .as_mut()
+            .ok_or_else(|| "No replay is open".to_string())?;
+        replay.control(control)?;
+        Ok(replay.status())
+    }
+
+    /// Returns the replayed snapshot for the current playback position
+    ///
+    /// # Returns
+    ///
+    /// `None` while showing live data, otherwise the frame or an error string
+    /// if it cannot be read
+    pub fn replay_frame(&mut self) -> Option<Result<(Vec<ProcessInfo>, SystemStats), String>> {
+        self.replay.as_mut().map(Replay::current_frame)
+    }
+
+    /// Returns the process tracking rebuilt up to the current playback position
+    ///
+    /// # Returns
+    ///
+    /// `None` while showing live data, otherwise the monitor or an error
+    /// string if a frame cannot be read
+    pub fn replay_monitor(&mut self) -> Option<Result<&ProcessMonitor, String>> {
+        let replay = self.replay.as_mut()?;
+        Some(replay.views().map(|views| &views.processes))
+    }
+
+    /// Returns the replayed metrics between two points in capture time
+    ///
+    /// # Returns
+    ///
+    /// `None` while showing live data, otherwise the metrics or an error
+    /// string if a frame cannot be read
+    pub fn replay_history(&mut self, start: u64, end: u64) -> Option<Result<MetricsRange, String>> {
+        let replay = self.replay.as_mut()?;
+        Some(replay.views().map(|views| views.history.range(start, end)))
+    }
+}
+
+/// An open capture file receiving frames
+#[derive(Debug)]
+struct CaptureWriter {
+    /// Buffered capture file
+    file: BufWriter<File>,
+    /// Path of the capture file, for error messages
+    path: PathBuf,
+    /// Number of frames written
+    frames: usize,
+}
+
+impl CaptureWriter {
+    /// Creates the file and writes the header
+    fn create(path: &Path, hostname: Option<String>) -> Result<Self, String> {
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let mut writer = Self {
+            file: BufWriter::new(file),
+            path: path.to_path_buf(),
+            frames: 0,
+        };
+        let header = CaptureHeader {
+            format: CAPTURE_FORMAT.to_string(),
+            version: CAPTURE_VERSION,
+            hostname,
+        };
+        writer.write_line(&header)?;
+        Ok(writer)
+    }
+
+    /// Appends a frame and flushes it, so a crash loses at most this frame
+    fn write(&mut self, processes: &[ProcessInfo], stats: &SystemStats) -> Result<(), String> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let frame = CaptureFrameRef {
+            timestamp,
+            processes,
+            stats,
+        };
+        self.write_line(&frame)?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Serializes a value as one line
+    fn write_line(&mut self, value: &impl Serialize) -> Result<(), String> {
+        serde_json::to_writer(&mut self.file, value).map_err(|e| e.to_string())?;
+        self.file
+            .write_all(b"\n")
+            .and_then(|()| self.file.flush())
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))
+    }
+
+    /// Flushes the file and returns the number of frames written
+    fn finish(mut self) -> Result<usize, String> {
+        self.file
+            .flush()
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))?;
+        Ok(self.frames)
+    }
+}
+
+/// An indexed capture file and its playback clock
+#[derive(Debug)]
+struct Replay {
+    /// Path of the capture file
+    path: PathBuf,
+    /// Host the capture was taken on
+    hostname: Option<String>,
+    /// Capture file, read on demand
+    file: BufReader<File>,
+    /// Timestamp and byte offset of every frame, ordered by time
+    frames: Vec<(u64, u64)>,
+    /// Index of the frame shown when the clock was last changed
+    cursor: usize,
+    /// Capture time shown when the clock was last changed
+    position: u64,
+    /// Wall clock time at which playback started, while playing
+    playing_since: Option<Instant>,
+    /// Capture milliseconds played per wall clock millisecond
+    speed: f64,
+    /// Most recently decoded frame and its index
+    cached: Option<(usize, Vec<ProcessInfo>, SystemStats)>,
+    /// Views rebuilt from the frames up to the cursor
+    views: ReplayViews,
+}
+
+/// Process tracking and metrics history fed with replayed frames
+#[derive(Debug, Default)]
+struct ReplayViews {
+    /// Index of the last frame fed in
+    fed: Option<usize>,
+    /// Process histories and exits as of the last frame fed in
+    processes: ProcessMonitor,
+    /// Metrics history as of the last frame fed in
+    history: MetricsHistory,
+}
+
+impl Replay {
+    /// Reads the header and indexes the frames of a capture file
+    fn open(path: &Path) -> Result<Self, String> {
+        let read_error = |e: std::io::Error| format!("Failed to read {}: {}", path.display(), e);
+        let mut file = BufReader::new(File::open(path).map_err(read_error)?);
+
+        let mut line = String::new();
+        let header_length = file.read_line(&mut line).map_err(read_error)? as u64;
+        let header: CaptureHeader = serde_json::from_str(&line)
+            .ok()
+            .filter(|header: &CaptureHeader| header.format == CAPTURE_FORMAT)
+            .ok_or_else(|| format!("{} is not a NeoHtop capture", path.display()))?;
+        if header.version > CAPTURE_VERSION {
+            return Err(format!(
+                "{} was written by a newer version of NeoHtop",
+                path.display()
+            ));
+        }
+
+        let frames = index_frames(&mut file, header_length).map_err(read_error)?;
+        let position = frames
+            .first()
+            .map(|&(timestamp, _)| timestamp)
+            .ok_or_else(|| format!("{} does not contain any snapshots", path.display()))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            hostname: header.hostname,
+            file,
+            frames,
+            cursor: 0,
+            position,
+            playing_since: None,
+            speed: 1.0,
+            cached: None,
+            views: ReplayViews::default(),
+        })
+    }
+
+    /// Capture time currently shown, clamped to the end of the capture
+    fn current_time(&self) -> u64 {
+        let elapsed = self.playing_since.map_or(0, |since| {
+            (since.elapsed().as_millis() as f64 * self.speed) as u64
+        });
+        (self.position + elapsed).min(self.end_time())
+    }
+
+    /// Index of the frame currently shown
+    ///
+    /// While paused this is the cursor. While playing it is the last frame
+    /// the clock has reached, never going back before the cursor, so frames
+    /// sharing a timestamp are still shown in file order.
+    fn current_index(&self) -> usize {
+        if self.playing_since.is_none() {
+            return self.cursor;
+        }
+        let time = self.current_time();
+        self.frames
+            .partition_point(|&(timestamp, _)| timestamp <= time)
+            .saturating_sub(1)
+            .max(self.cursor)
+    }
+
+    fn last_index(&self) -> usize {
+        self.frames.len() - 1
+    }
+
+    fn start_time(&self) -> u64 {
+        self.frames[0].0
+    }
+
+    fn end_time(&self) -> u64 {
+        self.frames[self.frames.len() - 1].0
+    }
+
+    /// Applies a playback control
+    fn control(&mut self, control: ReplayControl) -> Result<(), String> {
+        // Fold the time played so far into the cursor before changing the clock
+        self.cursor = self.current_index();
+        self.position = self.current_time();
+        if self.playing_since.is_some() {
+            self.playing_since = Some(Instant::now());
+        }
+        match control {
+            ReplayControl::Play => {
+                if self.cursor == self.last_index() {
+                    self.cursor = 0;
+                    self.position = self.start_time();
+                }
+                self.playing_since = Some(Instant::now());
+            }
+            ReplayControl::Pause => self.playing_since = None,
+            ReplayControl::Seek { timestamp } => {
+                // The first frame at or after the requested time
+                self.cursor = self
+                    .frames
+                    .partition_point(|&(frame_time, _)| frame_time < timestamp)
+                    .min(self.last_index());
+                self.position = self.frames[self.cursor].0;
+            }
+            ReplayControl::Speed { speed } => {
+                if !(SPEED_RANGE.0..=SPEED_RANGE.1).contains(&speed) {
+                    return Err(format!(
+                        "Playback speed must be between {} and {}",
+                        SPEED_RANGE.0, SPEED_RANGE.1
+                    ));
+                }
+                self.speed = speed;
+            }
+        }
+        Ok(())
+    }
+
+    fn status(&self) -> ReplayStatus {
+        let position = self.current_time();
+        ReplayStatus {
+            path: self.path.to_string_lossy().into_owned(),
+            hostname: self.hostname.clone(),
+            start_time: self.start_time(),
+            end_time: self.end_time(),
+            position,
+            playing: self.playing_since.is_some() && self.current_index() < self.last_index(),
+            speed: self.speed,
+            frame_count: self.frames.len(),
+        }
+    }
+
+    /// Decodes the frame currently shown
+    fn current_frame(&mut self) -> Result<(Vec<ProcessInfo>, SystemStats), String> {
+        let index = self.current_index();
+        if let Some((cached, processes, stats)) = &self.cached {
+            if *cached == index {
+                return Ok((processes.clone(), stats.clone()));
+            }
+        }
+        let frame = self.read_frame(index)?;
+        self.cached = Some((index, frame.processes.clone(), frame.stats.clone()));
+        Ok((frame.processes, frame.stats))
+    }
+
+    /// Brings the views up to the frame currently shown
+    ///
+    /// Playing forward feeds only the new frames; moving backwards, or more
+    /// than `VIEW_WINDOW_MS` forwards, rebuilds the views from the frames
+    /// within `VIEW_WINDOW_MS` of the position.
+    fn views(&mut self) -> Result<&ReplayViews, String> {
+        let index = self.current_index();
+        // Moving backwards, or so far forwards that the frames in between
+        // fall outside the window, starts over from the window
+        let target = self.frames[index].0;
+        if self.views.fed.is_some_and(|fed| {
+            fed > index || target.saturating_sub(self.frames[fed].0) > VIEW_WINDOW_MS
+        }) {
+            self.views = ReplayViews::default();
+        }
+        let first = match self.views.fed {
+            Some(fed) => fed + 1,
+            None => {
+                let window_start = target.saturating_sub(VIEW_WINDOW_MS);
+                self.frames
+                    .partition_point(|&(timestamp, _)| timestamp < window_start)
+            }
+        };
+        for frame_index in first..=index {
+            let frame = self.read_frame(frame_index)?;
+            let timestamp = self.frames[frame_index].0;
+            self.views
+                .processes
+                .replay_processes(&frame.processes, timestamp / 1000);
+            self.views
+                .history
+                .record_sample(MetricsSample::from_stats(timestamp, &frame.stats));
+            self.views.fed = Some(frame_index);
+        }
+        Ok(&self.views)
+    }
+
+    /// Decodes one frame from the file
+    fn read_frame(&mut self, index: usize) -> Result<CaptureFrame, String> {
+        let read_error =
+            |e: std::io::Error| format!("Failed to read {}: {}", self.path.display(), e);
+        let mut line = String::new();
+        self.file
+            .seek(SeekFrom::Start(self.frames[index].1))
+            .and_then(|_| self.file.read_line(&mut line))
+            .map_err(read_error)?;
+        serde_json::from_str(&line)
+            .map_err(|e| format!("Corrupt snapshot in {}: {}", self.path.display(), e))
+    }
+}
+
+/// Records the timestamp and byte offset of every complete frame line
+///
+/// Lines that do not parse, such as one cut short by a crash, are skipped.
+fn index_frames(file: &mut impl BufRead, mut offset: u64) -> std::io::Result<Vec<(u64, u64)>> {
+    let mut frames: Vec<(u64, u64)> = Vec::new();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let length = file.read_line(&mut line)?;
+        if length == 0 {
+            break;
+        }
+        if let Ok(frame) = serde_json::from_str::<FrameTimestamp>(&line) {
+            frames.push((frame.timestamp, offset));
+        }
+        offset += length as u64;
+    }
+    // Clock adjustments while capturing can put frames out of order
+    frames.sort_by_key(|&(timestamp, _)| timestamp);
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitoring::{ProcessMonitor, SystemMonitor};
+    use sysinfo::{System, SystemExt};
+
+    /// Tests indexing frames and skipping a truncated last line
+    #[test]
+    fn test_index_frames() {
+        let content = "{\"timestamp\":2000,\"processes\":[]}\n\
+                       {\"timestamp\":1000,\"processes\":[]}\n\
+                       {\"timestamp\":3000,\"proc";
+        let mut reader = BufReader::new(content.as_bytes());
+        let frames = index_frames(&mut reader, 10).unwrap();
+        assert_eq!(frames, [(1000, 44), (2000, 10)]);
+    }
+
+    /// Tests capturing live snapshots and replaying them with clock controls
+    #[test]
+    fn test_capture_and_replay() {
+        let path =
+            std::env::temp_dir().join(format!("neohtop-capture-{}.jsonl", std::process::id()));
+        let mut sys = System::new();
+        sys.refresh_all();
+        let processes = ProcessMonitor::new().collect_processes(&sys).unwrap();
+        let stats = SystemMonitor::new().collect_stats(&sys);
+
+        let mut capture = SessionCapture::new();
+        capture
+            .start_capture(&path, Some("db-01".to_string()))
+            .unwrap();
+        capture.write_frame(&processes, &stats);
+        capture.write_frame(&processes[..1], &stats);
+        assert!(capture.open_replay(&path).is_err());
+        assert_eq!(capture.stop_capture().unwrap(), 2);
+        assert!(capture.replay_frame().is_none());
+
+        let status = capture.open_replay(&path).unwrap();
+        assert_eq!(status.frame_count, 2);
+        assert_eq!(status.hostname.as_deref(), Some("db-01"));
+        assert!(!status.playing);
+        let (replayed, _) = capture.replay_frame().unwrap().unwrap();
+        assert_eq!(replayed.len(), processes.len());
+
+        let status = capture
+            .control_replay(ReplayControl::Seek {
+                timestamp: u64::MAX,
+            })
+            .unwrap();
+        assert_eq!(status.position, status.end_time);
+        let (replayed, _) = capture.replay_frame().unwrap().unwrap();
+        assert_eq!(replayed.len(), 1);
+        let monitor = capture.replay_monitor().unwrap().unwrap();
+        assert_eq!(monitor.last_processes().len(), 1);
+        assert_eq!(monitor.exited_processes(&[]).len(), processes.len() - 1);
+        assert!(monitor.process_history(processes[0].key()).is_some());
+        let history = capture.replay_history(0, u64::MAX).unwrap().unwrap();
+        assert!(!history.samples.is_empty());
+
+        // Both frames may share a timestamp; seeking back still shows the first
+        capture
+            .control_replay(ReplayControl::Seek { timestamp: 0 })
+            .unwrap();
+        let (replayed, _) = capture.replay_frame().unwrap().unwrap();
+        assert_eq!(replayed.len(), processes.len());
+        assert!(capture
+            .replay_monitor()
+            .unwrap()
+            .unwrap()
+            .exited_processes(&[])
+            .is_empty());
+        assert!(capture
+            .control_replay(ReplayControl::Speed { speed: 0.0 })
+            .is_err());
+
+        capture.close_replay();
+        assert!(capture.replay_status().is_none());
+        std::fs::remove_file(path).unwrap();
+    }
+}
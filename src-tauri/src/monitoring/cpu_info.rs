@@ -0,0 +1,256 @@
+//! CPU model, topology and frequency information
+//!
+//! This module describes the processor: model and vendor, how logical CPUs
+//! map onto cores, packages and NUMA nodes, the cache hierarchy and feature
+//! flags. It also reads live per-core frequencies and scaling governors, so
+//! hybrid CPUs with performance and efficiency cores can be told apart.
+
+#[cfg(target_os = "linux")]
+use super::{numa, procfs, CpuCache, CpuCoreInfo, NumaNodeCpus};
+use super::{CpuFrequency, CpuInfo};
+#[cfg(target_os = "linux")]
+use std::collections::{BTreeMap, HashMap, HashSet};
+#[cfg(target_os = "linux")]
+use std::path::Path;
+use sysinfo::{CpuExt, SystemExt};
+
+/// Root of the per-CPU sysfs directories
+#[cfg(target_os = "linux")]
+const CPU_SYSFS: &str = "/sys/devices/system/cpu";
+
+/// Reads static information about the processor
+///
+/// # Arguments
+///
+/// * `sys` - System information provider, used where sysfs is unavailable
+#[cfg(target_os = "linux")]
+pub fn read_cpu_info(sys: &sysinfo::System) -> CpuInfo {
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+    let field = |names: &[&str]| {
+        cpuinfo.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            names
+                .contains(&key.trim())
+                .then(|| value.trim().to_string())
+        })
+    };
+    let cores = read_cores(&online_cpus(sys.cpus().len()));
+    let packages: HashSet<u32> = cores.iter().filter_map(|core| core.package).collect();
+    let physical: HashSet<(Option<u32>, Option<u32>)> = cores
+        .iter()
+        .map(|core| (core.package, core.core_id))
+        .collect();
+
+    CpuInfo {
+        model_name: field(&["model name", "Model", "cpu model"])
+            .unwrap_or_else(|| sys.global_cpu_info().brand().to_string()),
+        vendor: field(&["vendor_id", "CPU implementer"])
+            .unwrap_or_else(|| sys.global_cpu_info().vendor_id().to_string()),
+        packages: packages.len().max(1),
+        physical_cores: sys.physical_core_count().unwrap_or(physical.len()),
+        logical_cpus: sys.cpus().len(),
+        caches: read_caches(),
+        numa_nodes: read_numa_nodes(),
+        // x86 calls them flags, ARM calls them features
+        flags: field(&["flags", "Features"])
+            .map(|flags| flags.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default(),
+        cores,
+    }
+}
+
+/// Reads static information about the processor
+#[cfg(not(target_os = "linux"))]
+pub fn read_cpu_info(sys: &sysinfo::System) -> CpuInfo {
+    CpuInfo {
+        model_name: sys.global_cpu_info().brand().to_string(),
+        vendor: sys.global_cpu_info().vendor_id().to_string(),
+        packages: 1,
+        physical_cores: sys.physical_core_count().unwrap_or(sys.cpus().len()),
+        logical_cpus: sys.cpus().len(),
+        caches: Vec::new(),
+        numa_nodes: Vec::new(),
+        flags: Vec::new(),
+        cores: Vec::new(),
+    }
+}
+
+/// Reads the live frequency and governor of every logical CPU
+///
+/// # Arguments
+///
+/// * `sys` - System information provider, used where cpufreq is unavailable
+#[cfg(target_os = "linux")]
+pub fn read_frequencies(sys: &sysinfo::System) -> Vec<CpuFrequency> {
+    online_cpus(sys.cpus().len())
+        .into_iter()
+        .zip(sys.cpus())
+        .map(|(cpu, info)| {
+            let base = Path::new(CPU_SYSFS).join(format!("cpu{}/cpufreq", cpu));
+            let mhz = |file: &str| procfs::read_u64(base.join(file)).map(|khz| khz / 1000);
+            CpuFrequency {
+                cpu,
+                current_mhz: mhz("scaling_cur_freq").unwrap_or_else(|| info.frequency()),
+                min_mhz: mhz("cpuinfo_min_freq"),
+                max_mhz: mhz("cpuinfo_max_freq"),
+                governor: procfs::read_string(base.join("scaling_governor")),
+            }
+        })
+        .collect()
+}
+
+/// Reads the live frequency of every logical CPU
+#[cfg(not(target_os = "linux"))]
+pub fn read_frequencies(sys: &sysinfo::System) -> Vec<CpuFrequency> {
+    sys.cpus()
+        .iter()
+        .enumerate()
+        .map(|(cpu, info)| CpuFrequency {
+            cpu,
+            current_mhz: info.frequency(),
+            min_mhz: None,
+            max_mhz: None,
+            governor: None,
+        })
+        .collect()
+}
+
+/// Returns the kernel numbers of the online logical CPUs, in ascending order
+///
+/// sysinfo lists only online CPUs, in the same order, so the n-th entry
+/// belongs to the n-th CPU of this list. Offline CPUs leave gaps in the
+/// numbering, so the position cannot be used as the sysfs `cpuN` number.
+///
+/// # Arguments
+///
+/// * `count` - Number of CPUs known to sysinfo, used if sysfs is unavailable
+#[cfg(target_os = "linux")]
+fn online_cpus(count: usize) -> Vec<usize> {
+    procfs::read_string(Path::new(CPU_SYSFS).join("online"))
+        .map(|list| procfs::parse_cpu_list(&list))
+        .filter(|cpus| !cpus.is_empty())
+        .unwrap_or_else(|| (0..count).collect())
+}
+
+/// Reads the topology of each logical CPU
+#[cfg(target_os = "linux")]
+fn read_cores(cpus: &[usize]) -> Vec<CpuCoreInfo> {
+    // Intel hybrid CPUs list their P-cores and E-cores as separate PMUs
+    let hybrid: HashMap<usize, &str> = [
+        ("/sys/devices/cpu_core/cpus", "performance"),
+        ("/sys/devices/cpu_atom/cpus", "efficiency"),
+    ]
+    .iter()
+    .filter_map(|(path, kind)| Some((procfs::read_string(path)?, *kind)))
+    .flat_map(|(list, kind)| {
+        procfs::parse_cpu_list(&list)
+            .into_iter()
+            .map(move |cpu| (cpu, kind))
+    })
+    .collect();
+
+    cpus.iter()
+        .map(|&cpu| {
+            let topology = Path::new(CPU_SYSFS).join(format!("cpu{}/topology", cpu));
+            let id = |file: &str| procfs::read_u64(topology.join(file)).map(|v| v as u32);
+            CpuCoreInfo {
+                cpu,
+                package: id("physical_package_id"),
+                core_id: id("core_id"),
+                core_type: hybrid.get(&cpu).map(|kind| kind.to_string()),
+            }
+        })
+        .collect()
+}
+
+/// Reads the cache hierarchy, counting how many instances of each cache exist
+#[cfg(target_os = "linux")]
+fn read_caches() -> Vec<CpuCache> {
+    let Ok(cpus) = std::fs::read_dir(CPU_SYSFS) else {
+        return Vec::new();
+    };
+    // A cache shared by several CPUs appears under each of them
+    let mut seen = HashSet::new();
+    let mut caches: BTreeMap<(u32, String, u64), usize> = BTreeMap::new();
+    for cpu in cpus.flatten() {
+        let Ok(indexes) = std::fs::read_dir(cpu.path().join("cache")) else {
+            continue;
+        };
+        for index in indexes.flatten() {
+            let path = index.path();
+            let (Some(level), Some(cache_type), Some(size), Some(shared)) = (
+                procfs::read_u64(path.join("level")),
+                procfs::read_string(path.join("type")),
+                procfs::read_string(path.join("size")).and_then(|s| parse_cache_size(&s)),
+                procfs::read_string(path.join("shared_cpu_list")),
+            ) else {
+                continue;
+            };
+            if seen.insert((level, cache_type.clone(), shared)) {
+                *caches.entry((level as u32, cache_type, size)).or_default() += 1;
+            }
+        }
+    }
+    caches
+        .into_iter()
+        .map(|((level, cache_type, size_bytes), instances)| CpuCache {
+            level,
+            cache_type,
+            size_bytes,
+            instances,
+        })
+        .collect()
+}
+
+/// Parses a sysfs cache size such as `32K` or `16M`
+#[cfg(target_os = "linux")]
+fn parse_cache_size(size: &str) -> Option<u64> {
+    let (digits, multiplier) = match size.as_bytes().last()? {
+        b'K' => (&size[..size.len() - 1], 1024),
+        b'M' => (&size[..size.len() - 1], 1024 * 1024),
+        b'G' => (&size[..size.len() - 1], 1024 * 1024 * 1024),
+        _ => (size, 1),
+    };
+    digits.parse::<u64>().ok().map(|value| value * multiplier)
+}
+
+/// Reads which logical CPUs belong to each NUMA node
+#[cfg(target_os = "linux")]
+fn read_numa_nodes() -> Vec<NumaNodeCpus> {
+    numa::read_nodes()
+        .into_iter()
+        .map(|node| NumaNodeCpus {
+            node: node.node,
+            cpus: node.cpus,
+        })
+        .collect()
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    /// Tests CPU list and cache size parsing
+    #[test]
+    fn test_parse_topology_values() {
+        assert_eq!(
+            procfs::parse_cpu_list("0-3,8,10-11\n"),
+            [0, 1, 2, 3, 8, 10, 11]
+        );
+        assert!(procfs::parse_cpu_list("").is_empty());
+        assert_eq!(parse_cache_size("32K"), Some(32 * 1024));
+        assert_eq!(parse_cache_size("16M"), Some(16 * 1024 * 1024));
+        assert_eq!(parse_cache_size("bogus"), None);
+    }
+
+    /// Tests reading CPU information from the running system
+    #[test]
+    fn test_read_cpu_info() {
+        let mut sys = sysinfo::System::new();
+        sys.refresh_cpu();
+        let info = read_cpu_info(&sys);
+        assert_eq!(info.logical_cpus, sys.cpus().len());
+        assert_eq!(info.cores.len(), info.logical_cpus);
+        assert_eq!(read_frequencies(&sys).len(), info.logical_cpus);
+    }
+}
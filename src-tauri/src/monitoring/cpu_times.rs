@@ -0,0 +1,199 @@
+//! CPU time breakdown
+//!
+//! This module parses `/proc/stat` between samples to split CPU time into
+//! user, system, iowait, steal and the other kernel accounting categories,
+//! per core and for the whole machine. Steal matters on virtual machines and
+//! iowait on storage-heavy hosts; a single usage percentage hides both.
+
+use super::CpuStats;
+#[cfg(target_os = "linux")]
+use super::CpuTimeBreakdown;
+use std::fmt::Debug;
+#[cfg(target_os = "linux")]
+use std::time::Instant;
+
+/// Cumulative jiffies of one CPU line in `/proc/stat`
+///
+/// Order: user, nice, system, idle, iowait, irq, softirq, steal, guest, guest_nice
+#[cfg(target_os = "linux")]
+type Jiffies = [u64; 10];
+
+/// One parsed sample of `/proc/stat`
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Default, PartialEq)]
+struct StatSample {
+    total: Jiffies,
+    /// Per-core jiffies, keyed by CPU index
+    cores: Vec<(usize, Jiffies)>,
+    context_switches: u64,
+    interrupts: u64,
+    forks: u64,
+}
+
+/// Tracks `/proc/stat` counters between samples
+#[derive(Debug, Default)]
+pub struct CpuTimesMonitor {
+    /// Time and contents of the previous sample
+    #[cfg(target_os = "linux")]
+    last_sample: Option<(Instant, StatSample)>,
+}
+
+impl CpuTimesMonitor {
+    /// Creates a new CPU time monitor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collects the CPU time breakdown since the previous call
+    ///
+    /// Returns `None` on the first call, since percentages need two samples.
+    #[cfg(target_os = "linux")]
+    pub fn collect_stats(&mut self) -> Option<CpuStats> {
+        let content = std::fs::read_to_string("/proc/stat").ok()?;
+        let sample = Self::parse_stat(&content)?;
+        let now = Instant::now();
+        let stats = self.last_sample.as_ref().and_then(|(time, last)| {
+            let elapsed = now.duration_since(*time).as_secs_f64();
+            Self::compute_stats(last, &sample, elapsed)
+        });
+        self.last_sample = Some((now, sample));
+        stats
+    }
+
+    /// Collects the CPU time breakdown since the previous call
+    #[cfg(not(target_os = "linux"))]
+    pub fn collect_stats(&mut self) -> Option<CpuStats> {
+        None
+    }
+
+    /// Parses the CPU lines and activity counters of `/proc/stat`
+    #[cfg(target_os = "linux")]
+    fn parse_stat(content: &str) -> Option<StatSample> {
+        let mut sample = StatSample::default();
+        let mut has_total = false;
+        for line in content.lines() {
+            let mut fields = line.split_whitespace();
+            let Some(key) = fields.next() else {
+                continue;
+            };
+            match key {
+                "cpu" => {
+                    sample.total = Self::parse_jiffies(fields);
+                    has_total = true;
+                }
+                "ctxt" => sample.context_switches = fields.next()?.parse().ok()?,
+                "intr" => sample.interrupts = fields.next()?.parse().ok()?,
+                "processes" => sample.forks = fields.next()?.parse().ok()?,
+                _ => {
+                    if let Some(index) = key.strip_prefix("cpu").and_then(|i| i.parse().ok()) {
+                        sample.cores.push((index, Self::parse_jiffies(fields)));
+                    }
+                }
+            }
+        }
+        has_total.then_some(sample)
+    }
+
+    /// Parses the jiffy columns of a CPU line; older kernels have fewer columns
+    #[cfg(target_os = "linux")]
+    fn parse_jiffies<'a>(fields: impl Iterator<Item = &'a str>) -> Jiffies {
+        let mut jiffies = Jiffies::default();
+        for (slot, value) in jiffies.iter_mut().zip(fields) {
+            *slot = value.parse().unwrap_or(0);
+        }
+        jiffies
+    }
+
+    /// Computes percentages and rates between two samples
+    #[cfg(target_os = "linux")]
+    fn compute_stats(last: &StatSample, current: &StatSample, elapsed: f64) -> Option<CpuStats> {
+        if elapsed <= 0.0 {
+            return None;
+        }
+        let rate = |current: u64, last: u64| current.checked_sub(last).map(|d| d as f64 / elapsed);
+        Some(CpuStats {
+            total: Self::breakdown(&last.total, &current.total)?,
+            cores: current
+                .cores
+                .iter()
+                .map(|(index, jiffies)| {
+                    last.cores
+                        .iter()
+                        .find(|(last_index, _)| last_index == index)
+                        .and_then(|(_, last)| Self::breakdown(last, jiffies))
+                        // A core that just came online has no history yet
+                        .unwrap_or_default()
+                })
+                .collect(),
+            context_switches_per_sec: rate(current.context_switches, last.context_switches)?,
+            interrupts_per_sec: rate(current.interrupts, last.interrupts)?,
+            forks_per_sec: rate(current.forks, last.forks)?,
+        })
+    }
+
+    /// Converts jiffy deltas into percentages of the elapsed CPU time
+    #[cfg(target_os = "linux")]
+    fn breakdown(last: &Jiffies, current: &Jiffies) -> Option<CpuTimeBreakdown> {
+        let mut delta = Jiffies::default();
+        for (i, slot) in delta.iter_mut().enumerate() {
+            *slot = current[i].checked_sub(last[i])?;
+        }
+        // Guest time is already included in user and nice time
+        let total: u64 = delta[..8].iter().sum();
+        if total == 0 {
+            return None;
+        }
+        let percent = |jiffies: u64| jiffies as f64 * 100.0 / total as f64;
+        Some(CpuTimeBreakdown {
+            user: percent(delta[0]),
+            nice: percent(delta[1]),
+            system: percent(delta[2]),
+            idle: percent(delta[3]),
+            iowait: percent(delta[4]),
+            irq: percent(delta[5]),
+            softirq: percent(delta[6]),
+            steal: percent(delta[7]),
+            guest: percent(delta[8] + delta[9]),
+        })
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    /// Tests parsing and percentage computation from /proc/stat samples
+    #[test]
+    fn test_cpu_times() {
+        let last = CpuTimesMonitor::parse_stat(
+            "cpu  100 0 50 800 40 0 10 0 0 0\n\
+             cpu0 50 0 25 400 20 0 5 0 0 0\n\
+             cpu1 50 0 25 400 20 0 5 0 0 0\n\
+             intr 1000 0 0\n\
+             ctxt 5000\n\
+             processes 300\n",
+        )
+        .unwrap();
+        let current = CpuTimesMonitor::parse_stat(
+            "cpu  150 0 100 1000 100 0 30 20 0 0\n\
+             cpu0 100 0 50 460 50 0 20 20 0 0\n\
+             cpu1 50 0 50 500 50 0 10 0 0 0\n\
+             intr 3000 0 0\n\
+             ctxt 9000\n\
+             processes 310\n",
+        )
+        .unwrap();
+        assert_eq!(current.cores.len(), 2);
+
+        let stats = CpuTimesMonitor::compute_stats(&last, &current, 2.0).unwrap();
+        assert_eq!(stats.total.user, 12.5);
+        assert_eq!(stats.total.iowait, 15.0);
+        assert_eq!(stats.total.steal, 5.0);
+        assert_eq!(stats.cores[0].steal, 10.0);
+        assert_eq!(stats.context_switches_per_sec, 2000.0);
+        assert_eq!(stats.interrupts_per_sec, 1000.0);
+        assert_eq!(stats.forks_per_sec, 5.0);
+
+        assert!(CpuTimesMonitor::compute_stats(&current, &last, 2.0).is_none());
+    }
+}
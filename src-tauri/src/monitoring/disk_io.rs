@@ -0,0 +1,294 @@
+//! Block device I/O statistics
+//!
+//! This module turns the cumulative counters in `/proc/diskstats` into
+//! throughput, IOPS, latency, queue depth and utilization for every block
+//! device, with partitions listed under the device they belong to. A busy
+//! disk is the usual reason a machine feels slow while the CPU is idle.
+
+use super::BlockDeviceStats;
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+use std::fmt::Debug;
+#[cfg(target_os = "linux")]
+use std::time::Instant;
+
+/// Size of the sectors counted in `/proc/diskstats`, regardless of hardware
+#[cfg(target_os = "linux")]
+const SECTOR_SIZE: u64 = 512;
+
+/// Cumulative counters of one block device
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct DiskCounters {
+    reads: u64,
+    sectors_read: u64,
+    read_ms: u64,
+    writes: u64,
+    sectors_written: u64,
+    write_ms: u64,
+    in_flight: u64,
+    io_ms: u64,
+    weighted_io_ms: u64,
+}
+
+#[cfg(target_os = "linux")]
+impl DiskCounters {
+    /// Returns the counter increments since an earlier sample
+    ///
+    /// Returns `None` if any counter went backwards, e.g. when a device was
+    /// detached and a new one reused its name.
+    fn since(&self, last: &Self) -> Option<Self> {
+        Some(Self {
+            reads: self.reads.checked_sub(last.reads)?,
+            sectors_read: self.sectors_read.checked_sub(last.sectors_read)?,
+            read_ms: self.read_ms.checked_sub(last.read_ms)?,
+            writes: self.writes.checked_sub(last.writes)?,
+            sectors_written: self.sectors_written.checked_sub(last.sectors_written)?,
+            write_ms: self.write_ms.checked_sub(last.write_ms)?,
+            in_flight: self.in_flight,
+            io_ms: self.io_ms.checked_sub(last.io_ms)?,
+            weighted_io_ms: self.weighted_io_ms.checked_sub(last.weighted_io_ms)?,
+        })
+    }
+}
+
+/// Tracks block device counters between samples
+#[derive(Debug, Default)]
+pub struct DiskIoMonitor {
+    /// Time and counters of the previous sample, keyed by device name
+    #[cfg(target_os = "linux")]
+    last_sample: Option<(Instant, HashMap<String, DiskCounters>)>,
+}
+
+impl DiskIoMonitor {
+    /// Creates a new disk I/O monitor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collects I/O statistics for every block device
+    ///
+    /// Rates are absent on the first sample, for devices that just appeared
+    /// and for devices whose counters went backwards.
+    #[cfg(target_os = "linux")]
+    pub fn collect_devices(&mut self) -> Vec<BlockDeviceStats> {
+        let Ok(content) = std::fs::read_to_string("/proc/diskstats") else {
+            return Vec::new();
+        };
+        let now = Instant::now();
+        let counters = Self::parse_diskstats(&content);
+
+        let previous = self.last_sample.as_ref().and_then(|(time, counters)| {
+            let elapsed_ms = now.duration_since(*time).as_secs_f64() * 1000.0;
+            (elapsed_ms > 0.0).then_some((elapsed_ms, counters))
+        });
+        let stats = counters
+            .iter()
+            // Never used devices, such as unattached loop devices, are noise
+            .filter(|(_, current)| current.reads + current.writes > 0)
+            .map(|(name, current)| {
+                let last = previous.and_then(|(elapsed_ms, counters)| {
+                    counters.get(name).map(|last| (elapsed_ms, last))
+                });
+                let parent = Self::partition_parent(name);
+                let mut stats = Self::device_stats(name, current, last);
+                // Partitions are as virtual as the device they belong to
+                stats.is_virtual = Self::is_virtual(parent.as_deref().unwrap_or(name));
+                (name.clone(), parent, stats)
+            })
+            .collect();
+
+        self.last_sample = Some((now, counters));
+        Self::roll_up(stats)
+    }
+
+    /// Collects I/O statistics for every block device
+    #[cfg(not(target_os = "linux"))]
+    pub fn collect_devices(&mut self) -> Vec<BlockDeviceStats> {
+        Vec::new()
+    }
+
+    /// Parses `/proc/diskstats` into counters keyed by device name
+    #[cfg(target_os = "linux")]
+    fn parse_diskstats(content: &str) -> HashMap<String, DiskCounters> {
+        content
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let value = |index: usize| fields.get(index)?.parse::<u64>().ok();
+                let counters = DiskCounters {
+                    reads: value(3)?,
+                    sectors_read: value(5)?,
+                    read_ms: value(6)?,
+                    writes: value(7)?,
+                    sectors_written: value(9)?,
+                    write_ms: value(10)?,
+                    in_flight: value(11)?,
+                    io_ms: value(12)?,
+                    weighted_io_ms: value(13)?,
+                };
+                Some((fields[2].to_string(), counters))
+            })
+            .collect()
+    }
+
+    /// Returns the device a partition belongs to, or `None` for whole devices
+    #[cfg(target_os = "linux")]
+    fn partition_parent(name: &str) -> Option<String> {
+        let path = std::path::Path::new("/sys/class/block").join(name);
+        if !path.join("partition").exists() {
+            return None;
+        }
+        // /sys/class/block/sda1 links to .../block/sda/sda1
+        let resolved = std::fs::canonicalize(path).ok()?;
+        Some(
+            resolved
+                .parent()?
+                .file_name()?
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+
+    /// Checks whether a whole device is virtual, i.e. has no backing hardware
+    #[cfg(target_os = "linux")]
+    fn is_virtual(name: &str) -> bool {
+        !std::path::Path::new("/sys/class/block")
+            .join(name)
+            .join("device")
+            .exists()
+    }
+
+    /// Derives rates from the current and previous counters of a device
+    #[cfg(target_os = "linux")]
+    fn device_stats(
+        name: &str,
+        current: &DiskCounters,
+        last: Option<(f64, &DiskCounters)>,
+    ) -> BlockDeviceStats {
+        let mut stats = BlockDeviceStats {
+            name: name.to_string(),
+            in_flight: current.in_flight,
+            ..Default::default()
+        };
+        let Some((elapsed_ms, last)) = last else {
+            return stats;
+        };
+        let Some(delta) = current.since(last) else {
+            return stats;
+        };
+
+        let per_sec = |count: u64| count as f64 * 1000.0 / elapsed_ms;
+        let latency = |ms: u64, requests: u64| (requests > 0).then(|| ms as f64 / requests as f64);
+        stats.read_bytes_per_sec = Some(per_sec(delta.sectors_read * SECTOR_SIZE));
+        stats.write_bytes_per_sec = Some(per_sec(delta.sectors_written * SECTOR_SIZE));
+        stats.reads_per_sec = Some(per_sec(delta.reads));
+        stats.writes_per_sec = Some(per_sec(delta.writes));
+        stats.read_latency_ms = latency(delta.read_ms, delta.reads);
+        stats.write_latency_ms = latency(delta.write_ms, delta.writes);
+        // Time-weighted I/O time per elapsed time is the average queue length
+        stats.queue_depth = Some(delta.weighted_io_ms as f64 / elapsed_ms);
+        stats.utilization_percent = Some((delta.io_ms as f64 / elapsed_ms * 100.0).min(100.0));
+        stats
+    }
+
+    /// Nests partitions under their device and sorts both levels by name
+    #[cfg(target_os = "linux")]
+    fn roll_up(stats: Vec<(String, Option<String>, BlockDeviceStats)>) -> Vec<BlockDeviceStats> {
+        let mut devices: HashMap<String, BlockDeviceStats> = HashMap::new();
+        let mut partitions = Vec::new();
+        for (name, parent, device) in stats {
+            match parent {
+                Some(parent) => partitions.push((parent, device)),
+                None => {
+                    devices.insert(name, device);
+                }
+            }
+        }
+        for (parent, partition) in partitions {
+            match devices.get_mut(&parent) {
+                Some(device) => device.partitions.push(partition),
+                // Parent without I/O of its own: show the partition on its own
+                None => {
+                    devices.insert(partition.name.clone(), partition);
+                }
+            }
+        }
+
+        let mut devices: Vec<BlockDeviceStats> = devices.into_values().collect();
+        devices.sort_by(|a, b| a.name.cmp(&b.name));
+        for device in &mut devices {
+            device.partitions.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        devices
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    const DISKSTATS: &str = "\
+ 259       0 nvme0n1 1000 10 80000 500 2000 20 160000 4000 2 3000 4500 0 0 0 0 0 0
+ 259       1 nvme0n1p1 100 0 800 50 0 0 0 0 0 60 50 0 0 0 0 0 0
+   7       0 loop0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+";
+
+    /// Tests parsing of /proc/diskstats
+    #[test]
+    fn test_parse_diskstats() {
+        let counters = DiskIoMonitor::parse_diskstats(DISKSTATS);
+        assert_eq!(counters.len(), 3);
+        let nvme = counters["nvme0n1"];
+        assert_eq!(nvme.sectors_read, 80000);
+        assert_eq!(nvme.write_ms, 4000);
+        assert_eq!(nvme.in_flight, 2);
+        assert_eq!(nvme.weighted_io_ms, 4500);
+    }
+
+    /// Tests rate derivation and partition rollup
+    #[test]
+    fn test_device_stats_and_roll_up() {
+        let last = DiskCounters {
+            reads: 1000,
+            sectors_read: 80000,
+            read_ms: 500,
+            writes: 2000,
+            sectors_written: 160000,
+            write_ms: 4000,
+            in_flight: 0,
+            io_ms: 3000,
+            weighted_io_ms: 4500,
+        };
+        let current = DiskCounters {
+            reads: 1100,
+            sectors_read: 82048,
+            read_ms: 700,
+            in_flight: 4,
+            io_ms: 3500,
+            weighted_io_ms: 6500,
+            ..last
+        };
+        let stats = DiskIoMonitor::device_stats("sda", &current, Some((1000.0, &last)));
+        assert_eq!(stats.read_bytes_per_sec, Some(2048.0 * 512.0));
+        assert_eq!(stats.reads_per_sec, Some(100.0));
+        assert_eq!(stats.read_latency_ms, Some(2.0));
+        assert_eq!(stats.write_latency_ms, None);
+        assert_eq!(stats.queue_depth, Some(2.0));
+        assert_eq!(stats.utilization_percent, Some(50.0));
+
+        let reset = DiskIoMonitor::device_stats("sda", &last, Some((1000.0, &current)));
+        assert_eq!(reset.reads_per_sec, None);
+
+        let devices = DiskIoMonitor::roll_up(vec![
+            ("sda".to_string(), None, stats),
+            (
+                "sda1".to_string(),
+                Some("sda".to_string()),
+                DiskIoMonitor::device_stats("sda1", &current, None),
+            ),
+        ]);
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].partitions[0].name, "sda1");
+    }
+}
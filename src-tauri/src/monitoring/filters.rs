@@ -0,0 +1,42 @@
+//! Name filtering helpers
+//!
+//! Shared by every setting that lets the user include or exclude items by
+//! name, such as secret environment variables or network interfaces.
+
+/// Matches `text` against a glob where `*` matches any run of characters
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No wildcard at all: exact match
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests glob matching
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*TOKEN*", "GITHUB_TOKEN"));
+        assert!(glob_match("AWS_*", "AWS_SECRET_ACCESS_KEY"));
+        assert!(glob_match("*_KEY", "STRIPE_KEY"));
+        assert!(glob_match("PATH", "PATH"));
+        assert!(!glob_match("PATH", "MANPATH"));
+        assert!(!glob_match("*_KEY", "KEYBOARD"));
+        assert!(!glob_match("A*B*C", "ACB"));
+    }
+}
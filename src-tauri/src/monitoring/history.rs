@@ -0,0 +1,305 @@
+//! System metrics history
+//!
+//! Every collected `SystemStats` is condensed into a `MetricsSample` and kept
+//! in memory, so charts can be redrawn after a page reload or when a window
+//! is opened. Samples are
+//! stored in tiers of decreasing resolution: each tier averages the incoming
+//! samples into fixed-width buckets and drops the oldest bucket once full.
+
+use super::{MetricsRange, MetricsSample, SystemStats};
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Bucket width in milliseconds and number of buckets kept, finest first
+const TIERS: [(u64, usize); 2] = [
+    // 1 second resolution for 10 minutes
+    (1_000, 600),
+    // 1 minute resolution for 24 hours
+    (60_000, 1_440),
+];
+
+/// Bounded, downsampling store of system metrics
+#[derive(Debug)]
+pub struct MetricsHistory {
+    /// Storage tiers, finest resolution first
+    tiers: Vec<Tier>,
+}
+
+impl Default for MetricsHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsHistory {
+    /// Creates an empty history
+    pub fn new() -> Self {
+        Self {
+            tiers: TIERS
+                .iter()
+                .map(|&(resolution_ms, capacity)| Tier::new(resolution_ms, capacity))
+                .collect(),
+        }
+    }
+
+    /// Adds the statistics of one collection to every tier
+    pub fn record(&mut self, stats: &SystemStats) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        self.record_sample(MetricsSample::from_stats(timestamp, stats));
+    }
+
+    /// Adds a sample to every tier
+    pub fn record_sample(&mut self, sample: MetricsSample) {
+        for tier in &mut self.tiers {
+            tier.record(&sample);
+        }
+    }
+
+    /// Returns the samples between two points in time
+    ///
+    /// The finest tier that reaches back to `start` is used. If no tier does,
+    /// the one holding the oldest data is used instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - Start of the range in milliseconds since the Unix epoch
+    /// * `end` - End of the range in milliseconds since the Unix epoch
+    pub fn range(&self, start: u64, end: u64) -> MetricsRange {
+        let oldest = self.tiers.iter().filter_map(Tier::oldest).min();
+        let start_covered = oldest.map_or(start, |oldest| start.max(oldest));
+        let tier = self
+            .tiers
+            .iter()
+            .find(|tier| tier.oldest().is_some_and(|oldest| oldest <= start_covered))
+            .unwrap_or(&self.tiers[0]);
+
+        MetricsRange {
+            resolution_ms: tier.resolution_ms,
+            samples: tier.range(start, end),
+        }
+    }
+}
+
+/// Samples of a single resolution
+#[derive(Debug)]
+struct Tier {
+    /// Width of a bucket in milliseconds
+    resolution_ms: u64,
+    /// Maximum number of completed buckets kept
+    capacity: usize,
+    /// Completed buckets, oldest first
+    samples: VecDeque<MetricsSample>,
+    /// Bucket still receiving samples
+    pending: Option<Bucket>,
+}
+
+impl Tier {
+    fn new(resolution_ms: u64, capacity: usize) -> Self {
+        Self {
+            resolution_ms,
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+            pending: None,
+        }
+    }
+
+    /// Adds a sample, completing the pending bucket when a new one starts
+    fn record(&mut self, sample: &MetricsSample) {
+        let start = sample.timestamp - sample.timestamp % self.resolution_ms;
+        if self
+            .pending
+            .as_ref()
+            .is_some_and(|bucket| bucket.start != start)
+        {
+            if let Some(bucket) = self.pending.take() {
+                if self.samples.len() == self.capacity {
+                    self.samples.pop_front();
+                }
+                self.samples.push_back(bucket.average());
+            }
+        }
+        self.pending
+            .get_or_insert_with(|| Bucket::new(start))
+            .add(sample);
+    }
+
+    /// Start of the oldest bucket, completed or not
+    fn oldest(&self) -> Option<u64> {
+        self.samples
+            .front()
+            .map(|sample| sample.timestamp)
+            .or_else(|| self.pending.as_ref().map(|bucket| bucket.start))
+    }
+
+    /// Returns the buckets overlapping `start..=end`, including the pending one
+    fn range(&self, start: u64, end: u64) -> Vec<MetricsSample> {
+        // A bucket starting before `start` still covers it if it ends after it
+        self.samples
+            .iter()
+            .cloned()
+            .chain(self.pending.as_ref().map(Bucket::average))
+            .filter(|sample| {
+                sample.timestamp + self.resolution_ms > start && sample.timestamp <= end
+            })
+            .collect()
+    }
+}
+
+/// Running sums of the samples falling into one bucket
+#[derive(Debug)]
+struct Bucket {
+    /// Start of the bucket in milliseconds since the Unix epoch
+    start: u64,
+    /// Number of samples added
+    count: u32,
+    /// Per-core sums; the fields below sum the sample field of the same name
+    cpu_usage: Vec<f64>,
+    memory_used: f64,
+    swap_used: f64,
+    network_rx_bytes: f64,
+    network_tx_bytes: f64,
+    disk_read_bytes: f64,
+    disk_write_bytes: f64,
+}
+
+impl Bucket {
+    fn new(start: u64) -> Self {
+        Self {
+            start,
+            count: 0,
+            cpu_usage: Vec::new(),
+            memory_used: 0.0,
+            swap_used: 0.0,
+            network_rx_bytes: 0.0,
+            network_tx_bytes: 0.0,
+            disk_read_bytes: 0.0,
+            disk_write_bytes: 0.0,
+        }
+    }
+
+    fn add(&mut self, sample: &MetricsSample) {
+        // Cores going offline leave their earlier usage in the sum
+        if self.cpu_usage.len() < sample.cpu_usage.len() {
+            self.cpu_usage.resize(sample.cpu_usage.len(), 0.0);
+        }
+        for (sum, usage) in self.cpu_usage.iter_mut().zip(&sample.cpu_usage) {
+            *sum += f64::from(*usage);
+        }
+        self.count += 1;
+        self.memory_used += sample.memory_used as f64;
+        self.swap_used += sample.swap_used as f64;
+        self.network_rx_bytes += sample.network_rx_bytes as f64;
+        self.network_tx_bytes += sample.network_tx_bytes as f64;
+        self.disk_read_bytes += sample.disk_read_bytes as f64;
+        self.disk_write_bytes += sample.disk_write_bytes as f64;
+    }
+
+    fn average(&self) -> MetricsSample {
+        let count = f64::from(self.count.max(1));
+        MetricsSample {
+            timestamp: self.start,
+            cpu_usage: self
+                .cpu_usage
+                .iter()
+                .map(|sum| (sum / count) as f32)
+                .collect(),
+            memory_used: (self.memory_used / count) as u64,
+            swap_used: (self.swap_used / count) as u64,
+            network_rx_bytes: (self.network_rx_bytes / count) as u64,
+            network_tx_bytes: (self.network_tx_bytes / count) as u64,
+            disk_read_bytes: (self.disk_read_bytes / count) as u64,
+            disk_write_bytes: (self.disk_write_bytes / count) as u64,
+        }
+    }
+}
+
+impl MetricsSample {
+    /// Condenses collected statistics into a sample
+    pub fn from_stats(timestamp: u64, stats: &SystemStats) -> Self {
+        // Virtual devices are skipped: their I/O is counted again on the
+        // disks beneath them, or never reaches a disk at all
+        let disk_rate = |rate: fn(&super::BlockDeviceStats) -> Option<f64>| {
+            stats
+                .block_devices
+                .iter()
+                .filter(|device| !device.is_virtual)
+                .filter_map(rate)
+                .sum::<f64>() as u64
+        };
+        Self {
+            timestamp,
+            cpu_usage: stats.cpu_usage.clone(),
+            memory_used: stats.memory_used,
+            swap_used: stats.swap.used,
+            network_rx_bytes: stats.network_rx_bytes,
+            network_tx_bytes: stats.network_tx_bytes,
+            disk_read_bytes: disk_rate(|device| device.read_bytes_per_sec),
+            disk_write_bytes: disk_rate(|device| device.write_bytes_per_sec),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp: u64, cpu: f32, memory_used: u64) -> MetricsSample {
+        MetricsSample {
+            timestamp,
+            cpu_usage: vec![cpu, cpu / 2.0],
+            memory_used,
+            swap_used: 0,
+            network_rx_bytes: 1000,
+            network_tx_bytes: 0,
+            disk_read_bytes: 0,
+            disk_write_bytes: 0,
+        }
+    }
+
+    /// Tests averaging samples into buckets and evicting the oldest
+    #[test]
+    fn test_tier_downsampling() {
+        let mut tier = Tier::new(1_000, 2);
+        tier.record(&sample(10_100, 10.0, 100));
+        tier.record(&sample(10_600, 30.0, 300));
+        tier.record(&sample(11_200, 50.0, 500));
+
+        let samples = tier.range(0, u64::MAX);
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].timestamp, 10_000);
+        assert_eq!(samples[0].cpu_usage, [20.0, 10.0]);
+        assert_eq!(samples[0].memory_used, 200);
+        assert_eq!(samples[1].timestamp, 11_000);
+
+        tier.record(&sample(12_000, 0.0, 0));
+        tier.record(&sample(13_000, 0.0, 0));
+        assert_eq!(tier.oldest(), Some(11_000));
+        assert_eq!(tier.range(12_000, 12_999).len(), 1);
+        assert_eq!(tier.range(12_500, 13_000).len(), 2);
+        assert_eq!(tier.range(13_000, 13_000)[0].timestamp, 13_000);
+    }
+
+    /// Tests that ranges reaching past the fine tier use the coarse tier
+    #[test]
+    fn test_range_tier_selection() {
+        let mut history = MetricsHistory::new();
+        for second in 0..900 {
+            history.record_sample(sample(1_700_000_000_000 + second * 1_000, 5.0, 100));
+        }
+        let end = 1_700_000_000_000 + 900 * 1_000;
+
+        let recent = history.range(end - 60_000, end);
+        assert_eq!(recent.resolution_ms, 1_000);
+        assert_eq!(recent.samples.len(), 60);
+
+        let all = history.range(end - 900_000, end);
+        assert_eq!(all.resolution_ms, 60_000);
+        // The first minute bucket starts before the range but overlaps it
+        assert_eq!(all.samples.len(), 16);
+        assert!(all.samples[0].timestamp < end - 900_000);
+        assert_eq!(all.samples[0].network_rx_bytes, 1000);
+    }
+}
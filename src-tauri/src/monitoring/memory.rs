@@ -0,0 +1,212 @@
+//! Memory statistics
+//!
+//! This module reads the detailed memory breakdown from `/proc/meminfo`, which
+//! sysinfo only summarises as total and used memory, together with the huge
+//! page pools and transparent huge page state from `/sys/kernel/mm`.
+
+use super::MemoryBreakdown;
+#[cfg(target_os = "linux")]
+use super::{procfs, HugePagePool, TransparentHugePages};
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::path::Path;
+
+/// Reads the current memory breakdown
+///
+/// # Returns
+///
+/// The breakdown, or `None` on platforms without `/proc/meminfo`
+#[cfg(target_os = "linux")]
+pub fn read_memory_breakdown() -> Option<MemoryBreakdown> {
+    let content = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let mut breakdown = parse_meminfo(&content);
+    breakdown.hugepage_pools = read_hugepage_pools(Path::new("/sys/kernel/mm/hugepages"));
+    breakdown.transparent_hugepages = read_transparent_hugepages();
+    Some(breakdown)
+}
+
+/// Reads the current memory breakdown
+#[cfg(not(target_os = "linux"))]
+pub fn read_memory_breakdown() -> Option<MemoryBreakdown> {
+    None
+}
+
+/// Parses the contents of `/proc/meminfo`
+#[cfg(target_os = "linux")]
+fn parse_meminfo(content: &str) -> MemoryBreakdown {
+    let fields: HashMap<&str, u64> = content
+        .lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let mut parts = rest.split_whitespace();
+            let value: u64 = parts.next()?.parse().ok()?;
+            // Everything except the HugePages_* counters is reported in kB
+            let value = match parts.next() {
+                Some("kB") => value * 1024,
+                _ => value,
+            };
+            Some((key.trim(), value))
+        })
+        .collect();
+    let field = |key: &str| fields.get(key).copied().unwrap_or(0);
+
+    MemoryBreakdown {
+        available: field("MemAvailable"),
+        buffers: field("Buffers"),
+        cached: field("Cached"),
+        shared: field("Shmem"),
+        slab_reclaimable: field("SReclaimable"),
+        slab_unreclaimable: field("SUnreclaim"),
+        dirty: field("Dirty"),
+        writeback: field("Writeback"),
+        anon: field("AnonPages"),
+        mapped: field("Mapped"),
+        hugepages_total: field("HugePages_Total"),
+        hugepages_free: field("HugePages_Free"),
+        hugepage_size: field("Hugepagesize"),
+        hugepage_pools: Vec::new(),
+        anon_hugepages: field("AnonHugePages"),
+        shmem_hugepages: field("ShmemHugePages"),
+        file_hugepages: field("FileHugePages"),
+        transparent_hugepages: None,
+        committed_as: field("Committed_AS"),
+        commit_limit: field("CommitLimit"),
+    }
+}
+
+/// Reads the huge page pools below `/sys/kernel/mm/hugepages`
+///
+/// Each pool is a `hugepages-<size>kB` directory holding page counts.
+#[cfg(target_os = "linux")]
+fn read_hugepage_pools(root: &Path) -> Vec<HugePagePool> {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut pools: Vec<HugePagePool> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let size_kb: u64 = name
+                .strip_prefix("hugepages-")?
+                .strip_suffix("kB")?
+                .parse()
+                .ok()?;
+            let path = entry.path();
+            let count = |file: &str| procfs::read_u64(path.join(file)).unwrap_or(0);
+            Some(HugePagePool {
+                page_size: size_kb * 1024,
+                total: count("nr_hugepages"),
+                free: count("free_hugepages"),
+                reserved: count("resv_hugepages"),
+                surplus: count("surplus_hugepages"),
+            })
+        })
+        .collect();
+    pools.sort_by_key(|pool| pool.page_size);
+    pools
+}
+
+/// Reads the transparent huge page modes and the `thp_*` counters of `/proc/vmstat`
+#[cfg(target_os = "linux")]
+fn read_transparent_hugepages() -> Option<TransparentHugePages> {
+    let root = Path::new("/sys/kernel/mm/transparent_hugepage");
+    let enabled = parse_selected_mode(&procfs::read_string(root.join("enabled"))?)?;
+    let defrag = procfs::read_string(root.join("defrag"))
+        .and_then(|modes| parse_selected_mode(&modes))
+        .unwrap_or_default();
+    let vmstat = std::fs::read_to_string("/proc/vmstat").unwrap_or_default();
+    let counters = procfs::parse_key_values(&vmstat);
+    let counter = |key: &str| counters.get(key).copied().unwrap_or(0);
+
+    Some(TransparentHugePages {
+        enabled,
+        defrag,
+        fault_alloc: counter("thp_fault_alloc"),
+        fault_fallback: counter("thp_fault_fallback"),
+        collapse_alloc: counter("thp_collapse_alloc"),
+        split_page: counter("thp_split_page"),
+    })
+}
+
+/// Extracts the active mode from a sysfs choice list such as `always [madvise] never`
+#[cfg(target_os = "linux")]
+fn parse_selected_mode(modes: &str) -> Option<String> {
+    modes
+        .split_whitespace()
+        .find_map(|mode| mode.strip_prefix('[')?.strip_suffix(']'))
+        .map(str::to_string)
+}
+
+impl MemoryBreakdown {
+    /// Memory that the kernel can reclaim on demand
+    ///
+    /// Uses the same definition as htop: page cache plus reclaimable slab,
+    /// minus shared memory which cannot simply be dropped.
+    pub fn reclaimable_cache(&self) -> u64 {
+        (self.cached + self.slab_reclaimable).saturating_sub(self.shared)
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    /// Tests parsing of /proc/meminfo units and fields
+    #[test]
+    fn test_parse_meminfo() {
+        let content = "MemTotal:       16000000 kB\n\
+                       MemAvailable:    8000000 kB\n\
+                       Buffers:          100000 kB\n\
+                       Cached:          3000000 kB\n\
+                       Shmem:            500000 kB\n\
+                       SReclaimable:     200000 kB\n\
+                       AnonHugePages:     10240 kB\n\
+                       HugePages_Total:       4\n\
+                       Hugepagesize:       2048 kB\n";
+        let breakdown = parse_meminfo(content);
+        assert_eq!(breakdown.available, 8_000_000 * 1024);
+        assert_eq!(breakdown.hugepages_total, 4);
+        assert_eq!(breakdown.hugepage_size, 2048 * 1024);
+        assert_eq!(breakdown.anon_hugepages, 10240 * 1024);
+        assert_eq!(breakdown.dirty, 0);
+        assert_eq!(breakdown.reclaimable_cache(), 2_700_000 * 1024);
+    }
+
+    /// Tests extracting the active mode of a sysfs choice list
+    #[test]
+    fn test_parse_selected_mode() {
+        assert_eq!(
+            parse_selected_mode("always [madvise] never").as_deref(),
+            Some("madvise")
+        );
+        assert_eq!(parse_selected_mode("always defer madvise"), None);
+    }
+
+    /// Tests reading huge page pools from a fake sysfs tree
+    #[test]
+    fn test_read_hugepage_pools() {
+        let root = std::env::temp_dir().join(format!("neohtop-hugepages-{}", std::process::id()));
+        for (pool, total, free) in [
+            ("hugepages-1048576kB", "2", "1"),
+            ("hugepages-2048kB", "512", "100"),
+        ] {
+            let dir = root.join(pool);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("nr_hugepages"), format!("{}\n", total)).unwrap();
+            std::fs::write(dir.join("free_hugepages"), format!("{}\n", free)).unwrap();
+            std::fs::write(dir.join("resv_hugepages"), "3\n").unwrap();
+        }
+
+        let pools = read_hugepage_pools(&root);
+        assert_eq!(pools.len(), 2);
+        assert_eq!(pools[0].page_size, 2048 * 1024);
+        assert_eq!(pools[0].total, 512);
+        assert_eq!(pools[0].free, 100);
+        assert_eq!(pools[0].reserved, 3);
+        assert_eq!(pools[0].surplus, 0);
+        assert_eq!(pools[1].page_size, 1024 * 1024 * 1024);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -4,10 +4,49 @@
 //! and processes. It includes process monitoring, system statistics collection,
 //! and data structures for representing system state.
 
+mod aggregation;
+mod capture;
+mod cpu_info;
+mod cpu_times;
+mod disk_io;
+mod filters;
+mod history;
+mod memory;
+mod mounts;
+mod network;
+mod numa;
+mod power;
+mod pressure;
+mod process_actions;
+mod process_environment;
+mod process_history;
+mod process_lifecycle;
+mod process_memory;
 mod process_monitor;
+#[cfg(target_os = "linux")]
+mod procfs;
+mod recorder;
+mod sensors;
+mod sessions;
+mod settings_file;
+mod swap;
+mod system_info;
 mod system_monitor;
 mod types;
 
+pub use aggregation::{group_members, group_processes};
+pub use capture::SessionCapture;
+pub use cpu_info::read_cpu_info;
+pub use numa::read_process_memory as read_process_numa_memory;
+pub use pressure::{read_cgroup_pressure, read_process_cgroup_pressure};
+pub use process_actions::{ProcessActions, ProcessHandle};
+pub use process_environment::EnvironmentInspector;
+pub use process_lifecycle::ProcessLifecycleMonitor;
+pub use process_memory::{read_process_memory_details, read_process_pss};
 pub use process_monitor::ProcessMonitor;
+pub use recorder::{read_recordings, MetricsRecorder};
+pub use sensors::read_sensors;
+pub use sessions::{read_login_sessions, user_name};
+pub use system_info::read_system_info;
 pub use system_monitor::SystemMonitor;
 pub use types::*; // Re-export all types
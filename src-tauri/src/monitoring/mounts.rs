@@ -0,0 +1,367 @@
+//! Mounted filesystem statistics
+//!
+//! This module lists every mounted filesystem with its capacity and inode
+//! usage. Pseudo filesystems (procfs, tmpfs, overlay, snap squashfs images...)
+//! are hidden by a user configurable filter so the list stays focused on
+//! real storage.
+//!
+//! On Linux the usage of each mount is read with `statvfs` by a small pool of
+//! worker threads. A refresh never waits for them: it queues new readings and
+//! returns the last known usage. A call that hangs, as it does on an
+//! unreachable network filesystem, ties up a single worker, and the mount
+//! keeps its last known usage until the call returns.
+
+use super::filters::glob_match;
+use super::{MountFilter, MountInfo};
+#[cfg(target_os = "linux")]
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+#[cfg(target_os = "linux")]
+use std::sync::mpsc::{self, Sender};
+#[cfg(target_os = "linux")]
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Number of threads making `statvfs` calls
+#[cfg(target_os = "linux")]
+const STATVFS_WORKERS: usize = 4;
+
+/// Capacity and inode usage of one filesystem
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy)]
+struct MountUsage {
+    total_bytes: u64,
+    used_bytes: u64,
+    free_bytes: u64,
+    /// Total and free inodes, for filesystems with a fixed inode table
+    inodes: Option<(u64, u64)>,
+}
+
+/// Results of the `statvfs` calls, shared with the workers making them
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default)]
+struct UsageCache {
+    /// Last successful reading, keyed by mount point
+    usage: HashMap<String, MountUsage>,
+    /// Mount points whose call has not returned yet
+    pending: HashSet<String>,
+}
+
+/// Collects per-mount filesystem statistics
+#[derive(Debug, Default)]
+pub struct MountMonitor {
+    /// Filesystems selected for display
+    filter: MountFilter,
+    /// Usage readings, updated by the workers
+    #[cfg(target_os = "linux")]
+    usage: Arc<Mutex<UsageCache>>,
+    /// Queue of mount points to read, whose workers start on first use
+    #[cfg(target_os = "linux")]
+    requests: OnceLock<Sender<String>>,
+}
+
+impl MountMonitor {
+    /// Creates a new mount monitor with the default filter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current mount filter
+    pub fn filter(&self) -> &MountFilter {
+        &self.filter
+    }
+
+    /// Replaces the mount filter
+    pub fn set_filter(&mut self, filter: MountFilter) {
+        self.filter = filter;
+    }
+
+    /// Collects statistics for every mount that passes the filter
+    ///
+    /// # Arguments
+    ///
+    /// * `sys` - System information provider
+    pub fn collect_mounts(&self, sys: &sysinfo::System) -> Vec<MountInfo> {
+        // Filtering happens before statvfs, so excluded mounts are never queried
+        let included: Vec<MountInfo> = Self::read_mounts(sys)
+            .into_iter()
+            .filter(|mount| self.includes(mount))
+            .collect();
+        let mut mounts: Vec<MountInfo> = self
+            .fill_usage(included)
+            .into_iter()
+            .filter(|mount| mount.total_bytes > 0)
+            .collect();
+        mounts.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+        mounts
+    }
+
+    /// Checks whether a mount passes the filter
+    fn includes(&self, mount: &MountInfo) -> bool {
+        !self
+            .filter
+            .exclude_fs_types
+            .iter()
+            .any(|p| glob_match(p, &mount.fs_type))
+            && !self
+                .filter
+                .exclude_mount_points
+                .iter()
+                .any(|p| glob_match(p, &mount.mount_point))
+    }
+
+    /// Reads the mount table
+    #[cfg(target_os = "linux")]
+    fn read_mounts(_sys: &sysinfo::System) -> Vec<MountInfo> {
+        std::fs::read_to_string("/proc/self/mounts")
+            .map(|content| Self::parse_mounts(&content))
+            .unwrap_or_default()
+    }
+
+    /// Reads the mounted disks known to sysinfo
+    #[cfg(not(target_os = "linux"))]
+    fn read_mounts(sys: &sysinfo::System) -> Vec<MountInfo> {
+        use sysinfo::{DiskExt, SystemExt};
+
+        sys.disks()
+            .iter()
+            .map(|disk| MountInfo {
+                device: disk.name().to_string_lossy().into_owned(),
+                mount_point: disk.mount_point().to_string_lossy().into_owned(),
+                fs_type: String::from_utf8_lossy(disk.file_system()).into_owned(),
+                read_only: false,
+                total_bytes: disk.total_space(),
+                used_bytes: disk.total_space().saturating_sub(disk.available_space()),
+                free_bytes: disk.available_space(),
+                inodes_total: None,
+                inodes_used: None,
+                inodes_free: None,
+            })
+            .collect()
+    }
+
+    /// Parses `/proc/self/mounts` into mounts without usage figures
+    #[cfg(target_os = "linux")]
+    fn parse_mounts(content: &str) -> Vec<MountInfo> {
+        content
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let device = Self::unescape(fields.next()?);
+                let mount_point = Self::unescape(fields.next()?);
+                let fs_type = fields.next()?.to_string();
+                let read_only = fields
+                    .next()
+                    .is_some_and(|options| options.split(',').any(|o| o == "ro"));
+                Some(MountInfo {
+                    device,
+                    mount_point,
+                    fs_type,
+                    read_only,
+                    total_bytes: 0,
+                    used_bytes: 0,
+                    free_bytes: 0,
+                    inodes_total: None,
+                    inodes_used: None,
+                    inodes_free: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Decodes the octal escapes (`\040` for a space) used in the mount table
+    #[cfg(target_os = "linux")]
+    fn unescape(field: &str) -> String {
+        let bytes = field.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'\\' && i + 3 < bytes.len() {
+                let digits = std::str::from_utf8(&bytes[i + 1..i + 4]).ok();
+                if let Some(value) = digits.and_then(|d| u8::from_str_radix(d, 8).ok()) {
+                    decoded.push(value);
+                    i += 4;
+                    continue;
+                }
+            }
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+        String::from_utf8_lossy(&decoded).into_owned()
+    }
+
+    /// Fills in the last known capacity and inode usage and queues new readings
+    ///
+    /// Mounts whose usage has never been read successfully are dropped.
+    #[cfg(target_os = "linux")]
+    fn fill_usage(&self, mounts: Vec<MountInfo>) -> Vec<MountInfo> {
+        let requests = self
+            .requests
+            .get_or_init(|| Self::start_workers(Arc::clone(&self.usage)));
+        let Ok(mut state) = self.usage.lock() else {
+            return Vec::new();
+        };
+        state
+            .usage
+            .retain(|mount_point, _| mounts.iter().any(|m| &m.mount_point == mount_point));
+
+        // A mount whose previous call is still pending is not queried again,
+        // so a hung filesystem ties up a single worker
+        for mount in &mounts {
+            if state.pending.insert(mount.mount_point.clone())
+                && requests.send(mount.mount_point.clone()).is_err()
+            {
+                state.pending.remove(&mount.mount_point);
+            }
+        }
+
+        mounts
+            .into_iter()
+            .filter_map(|mut mount| {
+                let usage = state.usage.get(&mount.mount_point)?;
+                mount.total_bytes = usage.total_bytes;
+                mount.used_bytes = usage.used_bytes;
+                mount.free_bytes = usage.free_bytes;
+                if let Some((total, free)) = usage.inodes {
+                    mount.inodes_total = Some(total);
+                    mount.inodes_free = Some(free);
+                    mount.inodes_used = Some(total.saturating_sub(free));
+                }
+                Some(mount)
+            })
+            .collect()
+    }
+
+    /// Starts the workers reading queued mount points into `usage`
+    ///
+    /// The workers exit once the returned sender, owned by the monitor, is
+    /// dropped.
+    #[cfg(target_os = "linux")]
+    fn start_workers(usage: Arc<Mutex<UsageCache>>) -> Sender<String> {
+        let (sender, receiver) = mpsc::channel::<String>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..STATVFS_WORKERS {
+            let receiver = Arc::clone(&receiver);
+            let usage = Arc::clone(&usage);
+            let _ = std::thread::Builder::new()
+                .name("statvfs".into())
+                .spawn(move || loop {
+                    let request = match receiver.lock() {
+                        Ok(receiver) => receiver.recv(),
+                        Err(_) => break,
+                    };
+                    let Ok(mount_point) = request else {
+                        break;
+                    };
+                    let reading = Self::read_usage(&mount_point);
+                    let Ok(mut state) = usage.lock() else {
+                        break;
+                    };
+                    state.pending.remove(&mount_point);
+                    match reading {
+                        Some(reading) => state.usage.insert(mount_point, reading),
+                        None => state.usage.remove(&mount_point),
+                    };
+                });
+        }
+        sender
+    }
+
+    /// Capacity is already known from sysinfo
+    #[cfg(not(target_os = "linux"))]
+    fn fill_usage(&self, mounts: Vec<MountInfo>) -> Vec<MountInfo> {
+        mounts
+    }
+
+    /// Reads capacity and inode usage of a mount point with `statvfs`
+    #[cfg(target_os = "linux")]
+    fn read_usage(mount_point: &str) -> Option<MountUsage> {
+        let path = std::ffi::CString::new(mount_point).ok()?;
+        // SAFETY: statvfs is plain old data; an all-zero value is valid.
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        // SAFETY: `path` is NUL terminated and `stat` is a valid out pointer.
+        if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+            return None;
+        }
+
+        let block_size = stat.f_frsize as u64;
+        Some(MountUsage {
+            total_bytes: stat.f_blocks as u64 * block_size,
+            free_bytes: stat.f_bavail as u64 * block_size,
+            used_bytes: (stat.f_blocks as u64).saturating_sub(stat.f_bfree as u64) * block_size,
+            // Filesystems without a fixed inode table (btrfs, vfat...) report zero
+            inodes: (stat.f_files > 0).then_some((stat.f_files as u64, stat.f_ffree as u64)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mount(fs_type: &str, mount_point: &str) -> MountInfo {
+        MountInfo {
+            device: "/dev/sda1".to_string(),
+            mount_point: mount_point.to_string(),
+            fs_type: fs_type.to_string(),
+            read_only: false,
+            total_bytes: 1024,
+            used_bytes: 0,
+            free_bytes: 1024,
+            inodes_total: None,
+            inodes_used: None,
+            inodes_free: None,
+        }
+    }
+
+    /// Tests that pseudo filesystems are hidden by default
+    #[test]
+    fn test_mount_filter() {
+        let mut monitor = MountMonitor::new();
+        assert!(monitor.includes(&mount("ext4", "/")));
+        assert!(monitor.includes(&mount("xfs", "/var/lib/docker")));
+        assert!(!monitor.includes(&mount("tmpfs", "/run")));
+        assert!(!monitor.includes(&mount("squashfs", "/snap/core/1")));
+
+        monitor.set_filter(MountFilter {
+            exclude_fs_types: Vec::new(),
+            exclude_mount_points: vec!["/boot*".to_string()],
+        });
+        assert!(monitor.includes(&mount("tmpfs", "/run")));
+        assert!(!monitor.includes(&mount("vfat", "/boot/efi")));
+    }
+
+    /// Tests parsing of the mount table
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_mounts() {
+        let mounts = MountMonitor::parse_mounts(
+            "/dev/nvme0n1p2 / ext4 rw,relatime 0 0\n\
+             /dev/sdb1 /mnt/my\\040disk ext4 ro,nosuid 0 0\n",
+        );
+        assert_eq!(mounts.len(), 2);
+        assert_eq!(mounts[0].device, "/dev/nvme0n1p2");
+        assert!(!mounts[0].read_only);
+        assert_eq!(mounts[1].mount_point, "/mnt/my disk");
+        assert!(mounts[1].read_only);
+    }
+
+    /// Tests that usage read by the workers is filled in
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_collect_mounts() {
+        use sysinfo::SystemExt;
+
+        let sys = sysinfo::System::new();
+        let monitor = MountMonitor::new();
+        // Readings arrive in the background; the first refresh returns none
+        let mut mounts = monitor.collect_mounts(&sys);
+        for _ in 0..20 {
+            if !mounts.is_empty() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            mounts = monitor.collect_mounts(&sys);
+        }
+        assert!(mounts.iter().all(|mount| mount.total_bytes > 0));
+    }
+}
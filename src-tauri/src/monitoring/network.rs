@@ -0,0 +1,362 @@
+//! Network interface statistics
+//!
+//! This module reports traffic, packet and error counters for each network
+//! interface, together with link details read from sysfs on Linux. A user
+//! configurable filter decides which interfaces are shown and summed into
+//! the filtered totals; the overall totals always cover every interface.
+
+use super::filters::glob_match;
+#[cfg(target_os = "linux")]
+use super::procfs;
+use super::{NetworkFilter, NetworkInterface};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::time::Instant;
+use sysinfo::{NetworkExt, NetworksExt, SystemExt};
+
+/// Link details that sysinfo does not expose
+#[derive(Debug, Default)]
+struct LinkDetails {
+    link_state: Option<String>,
+    mtu: Option<u32>,
+    speed_mbps: Option<u32>,
+    is_virtual: bool,
+    rx_dropped: Option<u64>,
+    tx_dropped: Option<u64>,
+}
+
+/// Cumulative counters of one interface at one point in time
+#[derive(Debug, Clone, Copy)]
+struct InterfaceCounters {
+    rx_bytes: u64,
+    tx_bytes: u64,
+    rx_packets: u64,
+    tx_packets: u64,
+    sampled_at: Instant,
+}
+
+/// Per-second rates derived from two counter samples
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct InterfaceRates {
+    rx_bytes: u64,
+    tx_bytes: u64,
+    rx_packets: u64,
+    tx_packets: u64,
+}
+
+impl InterfaceCounters {
+    /// Computes rates against an earlier sample
+    ///
+    /// Returns `None` if no time has passed or any counter went backwards,
+    /// which happens when the interface was recreated or its driver reset
+    /// the statistics (e.g. a VPN reconnect).
+    fn rates_since(&self, previous: &Self) -> Option<InterfaceRates> {
+        let elapsed = self
+            .sampled_at
+            .checked_duration_since(previous.sampled_at)?
+            .as_secs_f64();
+        if elapsed <= 0.0 {
+            return None;
+        }
+        let rate = |current: u64, previous: u64| {
+            current
+                .checked_sub(previous)
+                .map(|delta| (delta as f64 / elapsed) as u64)
+        };
+        Some(InterfaceRates {
+            rx_bytes: rate(self.rx_bytes, previous.rx_bytes)?,
+            tx_bytes: rate(self.tx_bytes, previous.tx_bytes)?,
+            rx_packets: rate(self.rx_packets, previous.rx_packets)?,
+            tx_packets: rate(self.tx_packets, previous.tx_packets)?,
+        })
+    }
+}
+
+/// Collects per-interface network statistics
+#[derive(Debug, Default)]
+pub struct NetworkMonitor {
+    /// Interfaces selected for display and aggregation
+    filter: NetworkFilter,
+    /// Counters from the previous sample, keyed by interface name
+    ///
+    /// Kept for filtered interfaces too, so changing the filter does not
+    /// lose their baseline.
+    last_counters: HashMap<String, InterfaceCounters>,
+}
+
+impl NetworkMonitor {
+    /// Creates a new network monitor with the default filter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current interface filter
+    pub fn filter(&self) -> &NetworkFilter {
+        &self.filter
+    }
+
+    /// Replaces the interface filter
+    pub fn set_filter(&mut self, filter: NetworkFilter) {
+        self.filter = filter;
+    }
+
+    /// Collects statistics for every interface that passes the filter
+    ///
+    /// Rates are derived from the cumulative counters of each interface
+    /// rather than the sysinfo refresh deltas, so they stay correct however
+    /// often the network list is refreshed. An interface has no rates on its
+    /// first sample and on the sample after its counters were reset.
+    ///
+    /// # Arguments
+    ///
+    /// * `sys` - System information provider
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing:
+    /// * The interfaces that pass the filter, sorted by name
+    /// * Bytes received and transmitted per second over all interfaces
+    pub fn collect_interfaces(
+        &mut self,
+        sys: &sysinfo::System,
+    ) -> (Vec<NetworkInterface>, (u64, u64)) {
+        let now = Instant::now();
+        let mut addresses = Self::read_ip_addresses();
+        let mut counters = HashMap::new();
+        let mut totals = (0, 0);
+
+        let mut interfaces: Vec<NetworkInterface> = sys
+            .networks()
+            .iter()
+            .filter_map(|(name, data)| {
+                let current = InterfaceCounters {
+                    rx_bytes: data.total_received(),
+                    tx_bytes: data.total_transmitted(),
+                    rx_packets: data.total_packets_received(),
+                    tx_packets: data.total_packets_transmitted(),
+                    sampled_at: now,
+                };
+                let rates = self
+                    .last_counters
+                    .get(name)
+                    .and_then(|previous| current.rates_since(previous));
+                counters.insert(name.clone(), current);
+                if let Some(rates) = rates {
+                    totals.0 += rates.rx_bytes;
+                    totals.1 += rates.tx_bytes;
+                }
+
+                let details = Self::read_link_details(name);
+                if !self.includes(name, details.is_virtual) {
+                    return None;
+                }
+                Some(NetworkInterface {
+                    name: name.clone(),
+                    mac_address: data.mac_address().to_string(),
+                    ip_addresses: addresses.remove(name).unwrap_or_default(),
+                    link_state: details.link_state.unwrap_or_else(|| "unknown".to_string()),
+                    mtu: details.mtu,
+                    speed_mbps: details.speed_mbps,
+                    is_virtual: details.is_virtual,
+                    rx_bytes_per_sec: rates.map(|r| r.rx_bytes),
+                    tx_bytes_per_sec: rates.map(|r| r.tx_bytes),
+                    rx_packets_per_sec: rates.map(|r| r.rx_packets),
+                    tx_packets_per_sec: rates.map(|r| r.tx_packets),
+                    rx_bytes_total: data.total_received(),
+                    tx_bytes_total: data.total_transmitted(),
+                    rx_errors: data.total_errors_on_received(),
+                    tx_errors: data.total_errors_on_transmitted(),
+                    rx_dropped: details.rx_dropped,
+                    tx_dropped: details.tx_dropped,
+                })
+            })
+            .collect();
+        // Interfaces that vanished are dropped; if one comes back it starts
+        // over with a fresh baseline.
+        self.last_counters = counters;
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+        (interfaces, totals)
+    }
+
+    /// Checks whether an interface passes the filter
+    fn includes(&self, name: &str, is_virtual: bool) -> bool {
+        if self.filter.hide_virtual && is_virtual {
+            return false;
+        }
+        let included = self.filter.include.is_empty()
+            || self.filter.include.iter().any(|p| glob_match(p, name));
+        included && !self.filter.exclude.iter().any(|p| glob_match(p, name))
+    }
+
+    /// Checks whether an interface is virtual
+    #[cfg(target_os = "linux")]
+    fn is_virtual(name: &str) -> bool {
+        std::path::Path::new("/sys/devices/virtual/net")
+            .join(name)
+            .exists()
+    }
+
+    /// Checks whether an interface is virtual
+    #[cfg(not(target_os = "linux"))]
+    fn is_virtual(name: &str) -> bool {
+        name.starts_with("lo")
+    }
+
+    /// Reads link state, MTU, speed and drop counters from sysfs
+    #[cfg(target_os = "linux")]
+    fn read_link_details(name: &str) -> LinkDetails {
+        let base = std::path::Path::new("/sys/class/net").join(name);
+        LinkDetails {
+            link_state: procfs::read_string(base.join("operstate")),
+            mtu: procfs::read_u64(base.join("mtu")).map(|v| v as u32),
+            // Reading speed fails for virtual links and reports -1 without carrier
+            speed_mbps: procfs::read_string(base.join("speed"))
+                .and_then(|v| v.parse::<i64>().ok())
+                .filter(|v| *v > 0)
+                .map(|v| v as u32),
+            is_virtual: Self::is_virtual(name),
+            rx_dropped: procfs::read_u64(base.join("statistics/rx_dropped")),
+            tx_dropped: procfs::read_u64(base.join("statistics/tx_dropped")),
+        }
+    }
+
+    /// Reads link state, MTU, speed and drop counters
+    #[cfg(not(target_os = "linux"))]
+    fn read_link_details(name: &str) -> LinkDetails {
+        LinkDetails {
+            is_virtual: Self::is_virtual(name),
+            ..Default::default()
+        }
+    }
+
+    /// Lists the IP addresses assigned to each interface
+    #[cfg(target_os = "linux")]
+    fn read_ip_addresses() -> HashMap<String, Vec<String>> {
+        use std::ffi::CStr;
+        use std::net::{Ipv4Addr, Ipv6Addr};
+
+        let mut addresses: HashMap<String, Vec<String>> = HashMap::new();
+        let mut list: *mut libc::ifaddrs = std::ptr::null_mut();
+        // SAFETY: getifaddrs fills `list` with a linked list we free below.
+        if unsafe { libc::getifaddrs(&mut list) } != 0 {
+            return addresses;
+        }
+
+        let mut current = list;
+        while !current.is_null() {
+            // SAFETY: `current` is a non-null node of the list returned above.
+            let entry = unsafe { &*current };
+            current = entry.ifa_next;
+            if entry.ifa_addr.is_null() || entry.ifa_name.is_null() {
+                continue;
+            }
+            // SAFETY: ifa_addr is non-null and its family tells us the
+            // concrete sockaddr type; ifa_name is a NUL terminated string.
+            let (family, name) = unsafe {
+                (
+                    (*entry.ifa_addr).sa_family as libc::c_int,
+                    CStr::from_ptr(entry.ifa_name)
+                        .to_string_lossy()
+                        .into_owned(),
+                )
+            };
+            let address = match family {
+                libc::AF_INET => {
+                    // SAFETY: family AF_INET guarantees a sockaddr_in.
+                    let addr = unsafe { &*(entry.ifa_addr as *const libc::sockaddr_in) };
+                    Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr)).to_string()
+                }
+                libc::AF_INET6 => {
+                    // SAFETY: family AF_INET6 guarantees a sockaddr_in6.
+                    let addr = unsafe { &*(entry.ifa_addr as *const libc::sockaddr_in6) };
+                    Ipv6Addr::from(addr.sin6_addr.s6_addr).to_string()
+                }
+                _ => continue,
+            };
+            addresses.entry(name).or_default().push(address);
+        }
+
+        // SAFETY: `list` was allocated by getifaddrs and is freed exactly once.
+        unsafe { libc::freeifaddrs(list) };
+        addresses
+    }
+
+    /// Lists the IP addresses assigned to each interface
+    #[cfg(not(target_os = "linux"))]
+    fn read_ip_addresses() -> HashMap<String, Vec<String>> {
+        HashMap::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests interface include/exclude filtering
+    #[test]
+    fn test_interface_filter() {
+        let mut monitor = NetworkMonitor::new();
+        assert!(!monitor.includes("lo", true));
+        assert!(monitor.includes("docker0", true));
+
+        monitor.set_filter(NetworkFilter {
+            include: vec!["en*".to_string(), "wl*".to_string()],
+            exclude: vec!["enx*".to_string()],
+            hide_virtual: true,
+        });
+        assert!(monitor.includes("enp3s0", false));
+        assert!(monitor.includes("wlan0", false));
+        assert!(!monitor.includes("enx00e04c", false));
+        assert!(!monitor.includes("docker0", true));
+        assert!(!monitor.includes("ens5", true));
+    }
+
+    /// Tests rate calculation across counter resets
+    #[test]
+    fn test_rates_since() {
+        let start = Instant::now();
+        let previous = InterfaceCounters {
+            rx_bytes: 1_000,
+            tx_bytes: 500,
+            rx_packets: 10,
+            tx_packets: 5,
+            sampled_at: start,
+        };
+        let current = InterfaceCounters {
+            rx_bytes: 3_000,
+            tx_bytes: 1_500,
+            rx_packets: 30,
+            tx_packets: 15,
+            sampled_at: start + std::time::Duration::from_secs(2),
+        };
+        assert_eq!(
+            current.rates_since(&previous),
+            Some(InterfaceRates {
+                rx_bytes: 1_000,
+                tx_bytes: 500,
+                rx_packets: 10,
+                tx_packets: 5,
+            })
+        );
+
+        let reset = InterfaceCounters {
+            rx_bytes: 200,
+            ..current
+        };
+        assert_eq!(reset.rates_since(&previous), None);
+        assert_eq!(previous.rates_since(&current), None);
+    }
+
+    /// Tests that the first sample of an interface carries no rates
+    #[test]
+    fn test_first_sample() {
+        let mut sys = sysinfo::System::new();
+        sys.refresh_networks_list();
+        let mut monitor = NetworkMonitor::new();
+        let (first, totals) = monitor.collect_interfaces(&sys);
+        assert!(first.iter().all(|i| i.rx_bytes_per_sec.is_none()));
+        assert!(first.iter().all(|i| i.name != "lo"));
+        assert_eq!(totals, (0, 0));
+        assert_eq!(monitor.last_counters.len(), sys.networks().iter().count());
+    }
+}
@@ -0,0 +1,203 @@
+//! NUMA topology and memory statistics
+//!
+//! On multi-socket machines memory is split into nodes, each close to some
+//! CPUs and far from others. This module reports per-node memory usage,
+//! CPU lists, distances and allocation counters from
+//! `/sys/devices/system/node`, and how much of a process's memory sits on
+//! each node according to `/proc/<pid>/numa_maps`.
+
+#[cfg(target_os = "linux")]
+use super::{procfs, NumaNodeMemory};
+use super::{NumaNode, ProcessKey, ProcessMonitor, ProcessNumaMemory};
+#[cfg(target_os = "linux")]
+use std::collections::BTreeMap;
+#[cfg(target_os = "linux")]
+use std::path::Path;
+
+/// Reads every NUMA node
+///
+/// # Returns
+///
+/// The nodes sorted by index; empty on platforms without NUMA information
+#[cfg(target_os = "linux")]
+pub fn read_nodes() -> Vec<NumaNode> {
+    read_nodes_from(Path::new("/sys/devices/system/node"))
+}
+
+/// Reads every NUMA node
+#[cfg(not(target_os = "linux"))]
+pub fn read_nodes() -> Vec<NumaNode> {
+    Vec::new()
+}
+
+/// Reads how a process's memory is distributed across NUMA nodes
+///
+/// # Arguments
+///
+/// * `sys` - System information provider
+/// * `key` - Identity of the process
+///
+/// # Errors
+///
+/// Returns an error string if the process no longer exists, its PID was
+/// reused, or its `numa_maps` cannot be read
+#[cfg(target_os = "linux")]
+pub fn read_process_memory(
+    sys: &mut sysinfo::System,
+    key: ProcessKey,
+) -> Result<ProcessNumaMemory, String> {
+    ProcessMonitor::find_process(sys, key)?;
+    let content = std::fs::read_to_string(format!("/proc/{}/numa_maps", key.pid))
+        .map_err(|e| format!("Failed to read NUMA maps of process {}: {}", key.pid, e))?;
+    Ok(ProcessNumaMemory {
+        key,
+        nodes: parse_numa_maps(&content),
+    })
+}
+
+/// Reads how a process's memory is distributed across NUMA nodes
+#[cfg(not(target_os = "linux"))]
+pub fn read_process_memory(
+    sys: &mut sysinfo::System,
+    key: ProcessKey,
+) -> Result<ProcessNumaMemory, String> {
+    ProcessMonitor::find_process(sys, key)?;
+    Err("NUMA information is only available on Linux".to_string())
+}
+
+/// Reads the nodes below a sysfs node directory
+#[cfg(target_os = "linux")]
+fn read_nodes_from(root: &Path) -> Vec<NumaNode> {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut nodes: Vec<NumaNode> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let node = name.strip_prefix("node")?.parse().ok()?;
+            let path = entry.path();
+            let meminfo = std::fs::read_to_string(path.join("meminfo")).unwrap_or_default();
+            let meminfo = parse_node_meminfo(&meminfo);
+            let numastat = std::fs::read_to_string(path.join("numastat")).unwrap_or_default();
+            let numastat = procfs::parse_key_values(&numastat);
+            let memory_total = meminfo.get("MemTotal").copied().unwrap_or(0);
+            let memory_free = meminfo.get("MemFree").copied().unwrap_or(0);
+            Some(NumaNode {
+                node,
+                cpus: procfs::read_string(path.join("cpulist"))
+                    .map(|list| procfs::parse_cpu_list(&list))
+                    .unwrap_or_default(),
+                memory_total,
+                memory_free,
+                memory_used: memory_total.saturating_sub(memory_free),
+                distances: procfs::read_string(path.join("distance"))
+                    .map(|d| {
+                        d.split_whitespace()
+                            .filter_map(|v| v.parse().ok())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                numa_hit: numastat.get("numa_hit").copied().unwrap_or(0),
+                numa_miss: numastat.get("numa_miss").copied().unwrap_or(0),
+                local_node: numastat.get("local_node").copied().unwrap_or(0),
+                other_node: numastat.get("other_node").copied().unwrap_or(0),
+            })
+        })
+        .collect();
+    nodes.sort_by_key(|node| node.node);
+    nodes
+}
+
+/// Parses a node `meminfo` file (`Node 0 MemTotal: 5340920 kB`) into bytes
+#[cfg(target_os = "linux")]
+fn parse_node_meminfo(content: &str) -> BTreeMap<&str, u64> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let key = key.split_whitespace().nth(2)?;
+            let mut parts = value.split_whitespace();
+            let value: u64 = parts.next()?.parse().ok()?;
+            let value = match parts.next() {
+                Some("kB") => value * 1024,
+                _ => value,
+            };
+            Some((key, value))
+        })
+        .collect()
+}
+
+/// Sums the pages per node across all mappings in `numa_maps`
+#[cfg(target_os = "linux")]
+fn parse_numa_maps(content: &str) -> Vec<NumaNodeMemory> {
+    let mut bytes_per_node: BTreeMap<usize, u64> = BTreeMap::new();
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let page_size = fields
+            .iter()
+            .find_map(|field| field.strip_prefix("kernelpagesize_kB="))
+            .and_then(|kb| kb.parse::<u64>().ok())
+            .unwrap_or(4)
+            * 1024;
+        for field in &fields {
+            let Some((node, pages)) = field
+                .strip_prefix('N')
+                .and_then(|field| field.split_once('='))
+            else {
+                continue;
+            };
+            if let (Ok(node), Ok(pages)) = (node.parse::<usize>(), pages.parse::<u64>()) {
+                *bytes_per_node.entry(node).or_default() += pages * page_size;
+            }
+        }
+    }
+    bytes_per_node
+        .into_iter()
+        .map(|(node, bytes)| NumaNodeMemory { node, bytes })
+        .collect()
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    /// Tests summing numa_maps pages per node
+    #[test]
+    fn test_parse_numa_maps() {
+        let nodes = parse_numa_maps(
+            "5558747c0000 default file=/usr/bin/head mapped=2 N0=2 kernelpagesize_kB=4\n\
+             7f0000000000 bind:1 anon=512 dirty=512 N0=12 N1=500 kernelpagesize_kB=4\n\
+             7f2000000000 default huge anon=2 N1=2 kernelpagesize_kB=2048\n",
+        );
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].bytes, 14 * 4096);
+        assert_eq!(nodes[1].bytes, 500 * 4096 + 2 * 2048 * 1024);
+    }
+
+    /// Tests reading node statistics from a fake sysfs tree
+    #[test]
+    fn test_read_nodes() {
+        let root = std::env::temp_dir().join(format!("neohtop-numa-{}", std::process::id()));
+        let node = root.join("node1");
+        std::fs::create_dir_all(&node).unwrap();
+        std::fs::write(node.join("cpulist"), "8-15\n").unwrap();
+        std::fs::write(node.join("distance"), "21 10\n").unwrap();
+        std::fs::write(
+            node.join("meminfo"),
+            "Node 1 MemTotal:        4000 kB\nNode 1 MemFree:         1000 kB\n",
+        )
+        .unwrap();
+        std::fs::write(node.join("numastat"), "numa_hit 500\nnuma_miss 7\n").unwrap();
+
+        let nodes = read_nodes_from(&root);
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].node, 1);
+        assert_eq!(nodes[0].cpus.len(), 8);
+        assert_eq!(nodes[0].memory_used, 3000 * 1024);
+        assert_eq!(nodes[0].distances, [21, 10]);
+        assert_eq!(nodes[0].numa_miss, 7);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
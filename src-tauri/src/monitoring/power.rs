@@ -0,0 +1,174 @@
+//! Battery and power supply status
+//!
+//! This module reads `/sys/class/power_supply` to report battery charge,
+//! state, power draw, estimated time remaining and wear, together with
+//! whether an AC adapter is connected. Machines without a battery report
+//! nothing.
+
+use super::PowerStats;
+#[cfg(target_os = "linux")]
+use super::{procfs, BatteryInfo};
+#[cfg(target_os = "linux")]
+use std::path::Path;
+
+/// Reads the current power supply status
+///
+/// # Returns
+///
+/// The status, or `None` on machines without a system battery
+#[cfg(target_os = "linux")]
+pub fn read_power_stats() -> Option<PowerStats> {
+    read_power_supplies(Path::new("/sys/class/power_supply"))
+}
+
+/// Reads the current power supply status
+#[cfg(not(target_os = "linux"))]
+pub fn read_power_stats() -> Option<PowerStats> {
+    None
+}
+
+/// Reads every power supply below `root`
+#[cfg(target_os = "linux")]
+fn read_power_supplies(root: &Path) -> Option<PowerStats> {
+    let mut batteries = Vec::new();
+    let mut ac_online = None;
+    for supply in std::fs::read_dir(root).ok()?.flatten() {
+        let path = supply.path();
+        match procfs::read_string(path.join("type")).as_deref() {
+            // Peripherals such as wireless mice report scope "Device"
+            Some("Battery")
+                if procfs::read_string(path.join("scope")).as_deref() != Some("Device") =>
+            {
+                let name = supply.file_name().to_string_lossy().into_owned();
+                batteries.push(read_battery(&path, name));
+            }
+            Some("Mains") | Some("USB") => {
+                let online = procfs::read_u64(path.join("online")) == Some(1);
+                ac_online = Some(ac_online.unwrap_or(false) || online);
+            }
+            _ => {}
+        }
+    }
+    if batteries.is_empty() {
+        return None;
+    }
+    batteries.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(PowerStats {
+        batteries,
+        ac_online,
+    })
+}
+
+/// Reads one battery
+///
+/// Drivers report either energy (µWh, µW) or charge (µAh, µA); charge values
+/// are converted to energy using the battery voltage.
+#[cfg(target_os = "linux")]
+fn read_battery(path: &Path, name: String) -> BatteryInfo {
+    let micro = |file: &str| procfs::read_u64(path.join(file)).map(|v| v as f64 / 1_000_000.0);
+    // Some drivers report the flow as negative while discharging
+    let signed_micro = |file: &str| {
+        procfs::read_string(path.join(file))?
+            .parse::<i64>()
+            .ok()
+            .map(|v| v.unsigned_abs() as f64 / 1_000_000.0)
+    };
+    let voltage = micro("voltage_min_design").or_else(|| micro("voltage_now"));
+    let energy = |kind: &str| {
+        micro(&format!("energy_{}", kind))
+            .or_else(|| Some(micro(&format!("charge_{}", kind))? * voltage?))
+    };
+    let energy_now_wh = energy("now");
+    let energy_full_wh = energy("full");
+    let energy_full_design_wh = energy("full_design");
+    let power_watts = signed_micro("power_now")
+        .or_else(|| Some(signed_micro("current_now")? * micro("voltage_now")?))
+        .filter(|watts| *watts > 0.0);
+    let state = procfs::read_string(path.join("status"))
+        .unwrap_or_else(|| "Unknown".to_string())
+        .to_lowercase();
+
+    // Estimates only make sense while energy is actually flowing
+    let hours = |energy: Option<f64>| Some(energy? / power_watts?);
+    let to_secs = |hours: f64| (hours * 3600.0) as u64;
+    let (time_to_empty_secs, time_to_full_secs) = match state.as_str() {
+        "discharging" => (hours(energy_now_wh).map(to_secs), None),
+        "charging" => (
+            None,
+            hours(
+                energy_full_wh
+                    .zip(energy_now_wh)
+                    .map(|(f, n)| (f - n).max(0.0)),
+            )
+            .map(to_secs),
+        ),
+        _ => (None, None),
+    };
+
+    BatteryInfo {
+        charge_percent: procfs::read_u64(path.join("capacity"))
+            .map(|percent| percent as f64)
+            .or_else(|| Some(energy_now_wh? / energy_full_wh? * 100.0)),
+        state,
+        power_watts,
+        time_to_empty_secs,
+        time_to_full_secs,
+        health_percent: energy_full_wh
+            .zip(energy_full_design_wh)
+            .filter(|(_, design)| *design > 0.0)
+            .map(|(full, design)| full / design * 100.0),
+        energy_now_wh,
+        energy_full_wh,
+        energy_full_design_wh,
+        cycle_count: procfs::read_u64(path.join("cycle_count")).filter(|count| *count > 0),
+        name,
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    /// Tests reading a charge-based battery and an AC adapter from a fake sysfs tree
+    #[test]
+    fn test_read_power_supplies() {
+        let root = std::env::temp_dir().join(format!("neohtop-power-{}", std::process::id()));
+        assert!(read_power_supplies(&root).is_none());
+
+        let battery = root.join("BAT0");
+        let adapter = root.join("AC");
+        let mouse = root.join("hidpp_battery_0");
+        for dir in [&battery, &adapter, &mouse] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        for (dir, file, content) in [
+            (&battery, "type", "Battery"),
+            (&battery, "status", "Discharging"),
+            (&battery, "capacity", "50"),
+            (&battery, "voltage_min_design", "10000000"),
+            (&battery, "voltage_now", "10000000"),
+            (&battery, "charge_now", "2000000"),
+            (&battery, "charge_full", "4000000"),
+            (&battery, "charge_full_design", "5000000"),
+            (&battery, "current_now", "-1000000"),
+            (&adapter, "type", "Mains"),
+            (&adapter, "online", "0"),
+            (&mouse, "type", "Battery"),
+            (&mouse, "scope", "Device"),
+        ] {
+            std::fs::write(dir.join(file), content).unwrap();
+        }
+
+        let stats = read_power_supplies(&root).unwrap();
+        assert_eq!(stats.ac_online, Some(false));
+        assert_eq!(stats.batteries.len(), 1);
+        let battery = &stats.batteries[0];
+        assert_eq!(battery.state, "discharging");
+        assert_eq!(battery.energy_now_wh, Some(20.0));
+        assert_eq!(battery.power_watts, Some(10.0));
+        assert_eq!(battery.time_to_empty_secs, Some(7200));
+        assert_eq!(battery.health_percent, Some(80.0));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
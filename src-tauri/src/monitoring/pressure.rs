@@ -0,0 +1,200 @@
+//! Pressure Stall Information
+//!
+//! This module reads Linux PSI, which measures the share of time tasks were
+//! stalled waiting for CPU, memory or I/O. Unlike the load average it tells
+//! apart the resource that is actually contended. Values are available
+//! system-wide from `/proc/pressure` and per cgroup on the unified (v2)
+//! hierarchy.
+
+#[cfg(target_os = "linux")]
+use super::{PressureAverages, PressureResource};
+use super::{PressureStats, ProcessCgroupPressure, ProcessKey, ProcessMonitor};
+#[cfg(target_os = "linux")]
+use std::path::{Component, Path};
+
+/// Mount point of the unified cgroup hierarchy
+#[cfg(target_os = "linux")]
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// Reads system-wide pressure
+///
+/// # Returns
+///
+/// The pressure, or `None` if the kernel was built without PSI or it is
+/// disabled (`psi=0`)
+#[cfg(target_os = "linux")]
+pub fn read_system_pressure() -> Option<PressureStats> {
+    read_pressure_files(|resource| format!("/proc/pressure/{}", resource))
+}
+
+/// Reads system-wide pressure
+#[cfg(not(target_os = "linux"))]
+pub fn read_system_pressure() -> Option<PressureStats> {
+    None
+}
+
+/// Reads the pressure of a single cgroup
+///
+/// # Arguments
+///
+/// * `cgroup` - Path relative to the cgroup v2 root, e.g. `/system.slice/docker.service`
+///
+/// # Errors
+///
+/// Returns an error string if the path escapes the cgroup hierarchy or the
+/// cgroup exposes no pressure files
+#[cfg(target_os = "linux")]
+pub fn read_cgroup_pressure(cgroup: &str) -> Result<PressureStats, String> {
+    let relative = Path::new(cgroup.trim_start_matches('/'));
+    if relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return Err(format!("Invalid cgroup path: {}", cgroup));
+    }
+    let directory = Path::new(CGROUP_ROOT).join(relative);
+    read_pressure_files(|resource| directory.join(format!("{}.pressure", resource)))
+        .ok_or_else(|| format!("No pressure information for cgroup {}", cgroup))
+}
+
+/// Reads the pressure of a single cgroup
+#[cfg(not(target_os = "linux"))]
+pub fn read_cgroup_pressure(_cgroup: &str) -> Result<PressureStats, String> {
+    Err("Pressure stall information is only available on Linux".to_string())
+}
+
+/// Reads the pressure of the cgroup a process belongs to
+///
+/// # Arguments
+///
+/// * `sys` - System information provider
+/// * `key` - Identity of the process
+///
+/// # Errors
+///
+/// Returns an error string if the process no longer exists, its PID was
+/// reused, it is not in a cgroup v2 hierarchy, or its cgroup exposes no
+/// pressure files
+#[cfg(target_os = "linux")]
+pub fn read_process_cgroup_pressure(
+    sys: &mut sysinfo::System,
+    key: ProcessKey,
+) -> Result<ProcessCgroupPressure, String> {
+    ProcessMonitor::find_process(sys, key)?;
+    let content = std::fs::read_to_string(format!("/proc/{}/cgroup", key.pid))
+        .map_err(|e| format!("Failed to read cgroup of process {}: {}", key.pid, e))?;
+    let cgroup = parse_unified_cgroup(&content)
+        .ok_or_else(|| format!("Process {} is not in a cgroup v2 hierarchy", key.pid))?;
+    Ok(ProcessCgroupPressure {
+        key,
+        pressure: read_cgroup_pressure(&cgroup)?,
+        cgroup,
+    })
+}
+
+/// Reads the pressure of the cgroup a process belongs to
+#[cfg(not(target_os = "linux"))]
+pub fn read_process_cgroup_pressure(
+    sys: &mut sysinfo::System,
+    key: ProcessKey,
+) -> Result<ProcessCgroupPressure, String> {
+    ProcessMonitor::find_process(sys, key)?;
+    Err("Pressure stall information is only available on Linux".to_string())
+}
+
+/// Extracts the cgroup v2 path from the contents of `/proc/<pid>/cgroup`
+///
+/// The unified hierarchy is the entry with ID 0 and no controllers, e.g.
+/// `0::/user.slice/user-1000.slice/session-2.scope`.
+#[cfg(target_os = "linux")]
+fn parse_unified_cgroup(content: &str) -> Option<String> {
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(str::to_string)
+}
+
+/// Reads the cpu, memory and io pressure files found at `path(resource)`
+#[cfg(target_os = "linux")]
+fn read_pressure_files<P: AsRef<Path>>(path: impl Fn(&str) -> P) -> Option<PressureStats> {
+    let read = |resource: &str| {
+        std::fs::read_to_string(path(resource))
+            .ok()
+            .and_then(|content| parse_pressure(&content))
+    };
+    let stats = PressureStats {
+        cpu: read("cpu"),
+        memory: read("memory"),
+        io: read("io"),
+    };
+    (stats.cpu.is_some() || stats.memory.is_some() || stats.io.is_some()).then_some(stats)
+}
+
+/// Parses the `some` and `full` lines of a pressure file
+#[cfg(target_os = "linux")]
+fn parse_pressure(content: &str) -> Option<PressureResource> {
+    let mut some = None;
+    let mut full = None;
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let kind = fields.next();
+        let mut averages = PressureAverages::default();
+        for field in fields {
+            let Some((key, value)) = field.split_once('=') else {
+                continue;
+            };
+            match key {
+                "avg10" => averages.avg10 = value.parse().ok()?,
+                "avg60" => averages.avg60 = value.parse().ok()?,
+                "avg300" => averages.avg300 = value.parse().ok()?,
+                "total" => averages.total_us = value.parse().ok()?,
+                _ => {}
+            }
+        }
+        match kind {
+            Some("some") => some = Some(averages),
+            Some("full") => full = Some(averages),
+            _ => {}
+        }
+    }
+    Some(PressureResource { some: some?, full })
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    /// Tests parsing of a pressure file
+    #[test]
+    fn test_parse_pressure() {
+        let resource = parse_pressure(
+            "some avg10=5.99 avg60=2.64 avg300=2.06 total=24639750\n\
+             full avg10=0.00 avg60=0.10 avg300=0.00 total=1200\n",
+        )
+        .unwrap();
+        assert_eq!(resource.some.avg10, 5.99);
+        assert_eq!(resource.some.total_us, 24639750);
+        assert_eq!(resource.full.unwrap().avg60, 0.10);
+
+        let cpu = parse_pressure("some avg10=1.00 avg60=0.50 avg300=0.25 total=10\n").unwrap();
+        assert!(cpu.full.is_none());
+        assert!(parse_pressure("").is_none());
+    }
+
+    /// Tests finding the unified hierarchy in a process cgroup file
+    #[test]
+    fn test_parse_unified_cgroup() {
+        assert_eq!(
+            parse_unified_cgroup("12:cpuset:/\n0::/system.slice/sshd.service\n").as_deref(),
+            Some("/system.slice/sshd.service")
+        );
+        assert_eq!(parse_unified_cgroup("4:memory:/user.slice\n"), None);
+    }
+
+    /// Tests that cgroup paths cannot escape the hierarchy
+    #[test]
+    fn test_cgroup_path_validation() {
+        assert!(read_cgroup_pressure("/../../etc").is_err());
+        assert!(read_cgroup_pressure("system.slice/../..").is_err());
+    }
+}
@@ -0,0 +1,297 @@
+//! Process actions
+//!
+//! This module sends signals to processes without ever hitting the wrong
+//! target. On Linux a pidfd is opened when the user selects a process, so
+//! later signals reach exactly that process even if its PID is reused. On
+//! kernels without pidfd support, and on other platforms, the process start
+//! time is re-verified immediately before every signal.
+
+use super::{ProcessKey, ProcessMonitor, ProcessSignal};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use sysinfo::{ProcessExt, ProcessStatus, Signal, SystemExt};
+
+/// Maximum number of process handles kept open at once
+const MAX_HANDLES: usize = 16;
+
+/// Interval between liveness checks when a pidfd is not available
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A verified reference to one specific process
+#[derive(Debug)]
+pub struct ProcessHandle {
+    /// Identity of the referenced process
+    key: ProcessKey,
+    /// Linux process file descriptor, if the kernel supports it
+    #[cfg(target_os = "linux")]
+    pidfd: Option<std::os::fd::OwnedFd>,
+}
+
+impl ProcessHandle {
+    /// Opens a handle to the process identified by `key`
+    ///
+    /// # Arguments
+    ///
+    /// * `sys` - System information provider
+    /// * `key` - Identity of the process
+    ///
+    /// # Returns
+    ///
+    /// The handle, or an error string if the process no longer exists or its
+    /// PID now belongs to a different process
+    pub fn open(sys: &mut sysinfo::System, key: ProcessKey) -> Result<Self, String> {
+        // The pidfd is opened before verifying the start time: if the
+        // verification passes, the descriptor is pinned to the right process.
+        #[cfg(target_os = "linux")]
+        let pidfd = pidfd::open(key.pid)?;
+
+        Self::verify(sys, key)?;
+        Ok(Self {
+            key,
+            #[cfg(target_os = "linux")]
+            pidfd,
+        })
+    }
+
+    /// Sends a signal to the referenced process
+    ///
+    /// # Errors
+    ///
+    /// Returns an error string if the process has exited, its PID was reused,
+    /// or the signal could not be delivered
+    pub fn send_signal(&self, signal: ProcessSignal) -> Result<(), String> {
+        #[cfg(target_os = "linux")]
+        if let Some(fd) = &self.pidfd {
+            return pidfd::send_signal(fd, signal)
+                .map_err(|e| format!("Failed to signal process {}: {}", self.key.pid, e));
+        }
+
+        let mut sys = sysinfo::System::new();
+        Self::verify(&mut sys, self.key)?;
+        let delivered = sys
+            .process(sysinfo::Pid::from(self.key.pid as usize))
+            .and_then(|process| process.kill_with(Self::to_sysinfo_signal(signal)))
+            .ok_or_else(|| format!("Signal {:?} is not supported on this platform", signal))?;
+        if delivered {
+            Ok(())
+        } else {
+            Err(format!("Failed to signal process {}", self.key.pid))
+        }
+    }
+
+    /// Waits until the referenced process exits
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - Maximum time to wait
+    ///
+    /// # Returns
+    ///
+    /// `true` if the process exited within `timeout`
+    pub fn wait_for_exit(&self, timeout: Duration) -> bool {
+        #[cfg(target_os = "linux")]
+        if let Some(fd) = &self.pidfd {
+            return pidfd::poll_exit(fd, timeout);
+        }
+
+        let deadline = Instant::now() + timeout;
+        let mut sys = sysinfo::System::new();
+        loop {
+            if !Self::is_alive(&mut sys, self.key) {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            thread::sleep(EXIT_POLL_INTERVAL);
+        }
+    }
+
+    /// Checks that the PID still belongs to the process identified by `key`
+    fn verify(sys: &mut sysinfo::System, key: ProcessKey) -> Result<(), String> {
+        ProcessMonitor::find_process(sys, key).map(|_| ())
+    }
+
+    /// Checks whether the process is still running (zombies count as exited)
+    fn is_alive(sys: &mut sysinfo::System, key: ProcessKey) -> bool {
+        Self::verify(sys, key).is_ok()
+            && sys
+                .process(sysinfo::Pid::from(key.pid as usize))
+                .is_some_and(|process| process.status() != ProcessStatus::Zombie)
+    }
+
+    /// Maps a frontend signal onto the sysinfo equivalent
+    fn to_sysinfo_signal(signal: ProcessSignal) -> Signal {
+        match signal {
+            ProcessSignal::Hup => Signal::Hangup,
+            ProcessSignal::Int => Signal::Interrupt,
+            ProcessSignal::Quit => Signal::Quit,
+            ProcessSignal::Kill => Signal::Kill,
+            ProcessSignal::Term => Signal::Term,
+            ProcessSignal::Stop => Signal::Stop,
+            ProcessSignal::Cont => Signal::Continue,
+            ProcessSignal::Usr1 => Signal::User1,
+            ProcessSignal::Usr2 => Signal::User2,
+        }
+    }
+}
+
+/// Keeps handles to the processes currently selected in the UI
+#[derive(Debug, Default)]
+pub struct ProcessActions {
+    /// Open handles, keyed by process identity
+    handles: HashMap<ProcessKey, Arc<ProcessHandle>>,
+}
+
+impl ProcessActions {
+    /// Creates an empty action layer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores a handle for a process the user has selected
+    ///
+    /// When the limit of open handles is reached an arbitrary older handle is
+    /// dropped; it is transparently reopened if it is needed again.
+    pub fn insert(&mut self, handle: ProcessHandle) {
+        if self.handles.len() >= MAX_HANDLES && !self.handles.contains_key(&handle.key) {
+            if let Some(evicted) = self.handles.keys().next().copied() {
+                self.handles.remove(&evicted);
+            }
+        }
+        self.handles.insert(handle.key, Arc::new(handle));
+    }
+
+    /// Returns the stored handle for a process, if it was selected
+    pub fn handle(&self, key: &ProcessKey) -> Option<Arc<ProcessHandle>> {
+        self.handles.get(key).cloned()
+    }
+
+    /// Drops the stored handle for a process
+    pub fn release(&mut self, key: &ProcessKey) {
+        self.handles.remove(key);
+    }
+}
+
+/// Thin wrappers around the Linux pidfd system calls
+#[cfg(target_os = "linux")]
+mod pidfd {
+    use super::ProcessSignal;
+    use std::io;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::time::Duration;
+
+    /// Opens a pidfd, returning `None` on kernels without pidfd support
+    pub fn open(pid: u32) -> Result<Option<OwnedFd>, String> {
+        // SAFETY: pidfd_open takes a PID and flags and returns a new descriptor.
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+        if fd >= 0 {
+            // SAFETY: the kernel just handed us ownership of this descriptor.
+            return Ok(Some(unsafe { OwnedFd::from_raw_fd(fd as libc::c_int) }));
+        }
+        let error = io::Error::last_os_error();
+        match error.raw_os_error() {
+            Some(libc::ESRCH) => Err(format!("Process {} no longer exists", pid)),
+            // ENOSYS: kernel older than 5.3; EPERM/EACCES: seccomp filters
+            _ => Ok(None),
+        }
+    }
+
+    /// Sends a signal through a pidfd
+    pub fn send_signal(fd: &OwnedFd, signal: ProcessSignal) -> io::Result<()> {
+        // SAFETY: `fd` is a valid pidfd; a null siginfo is explicitly allowed.
+        let result = unsafe {
+            libc::syscall(
+                libc::SYS_pidfd_send_signal,
+                fd.as_raw_fd(),
+                to_raw_signal(signal),
+                std::ptr::null::<libc::siginfo_t>(),
+                0,
+            )
+        };
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Waits for the pidfd to become readable, which happens on process exit
+    pub fn poll_exit(fd: &OwnedFd, timeout: Duration) -> bool {
+        let mut pollfd = libc::pollfd {
+            fd: fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout_ms = timeout.as_millis().min(libc::c_int::MAX as u128) as libc::c_int;
+        // SAFETY: `pollfd` is a valid, initialised array of length one.
+        let ready = unsafe { libc::poll(&mut pollfd, 1, timeout_ms) };
+        ready > 0 && pollfd.revents & libc::POLLIN != 0
+    }
+
+    fn to_raw_signal(signal: ProcessSignal) -> libc::c_int {
+        match signal {
+            ProcessSignal::Hup => libc::SIGHUP,
+            ProcessSignal::Int => libc::SIGINT,
+            ProcessSignal::Quit => libc::SIGQUIT,
+            ProcessSignal::Kill => libc::SIGKILL,
+            ProcessSignal::Term => libc::SIGTERM,
+            ProcessSignal::Stop => libc::SIGSTOP,
+            ProcessSignal::Cont => libc::SIGCONT,
+            ProcessSignal::Usr1 => libc::SIGUSR1,
+            ProcessSignal::Usr2 => libc::SIGUSR2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sysinfo::System;
+
+    fn spawn_sleeper(sys: &mut System) -> (std::process::Child, ProcessKey) {
+        let child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .expect("failed to spawn sleep");
+        let pid = sysinfo::Pid::from(child.id() as usize);
+        sys.refresh_process(pid);
+        let start_time = sys.process(pid).map(|p| p.start_time()).unwrap_or(0);
+        let key = ProcessKey {
+            pid: child.id(),
+            start_time,
+        };
+        (child, key)
+    }
+
+    /// Tests signalling a process and confirming its exit
+    #[cfg(unix)]
+    #[test]
+    fn test_signal_and_wait() {
+        let mut sys = System::new();
+        let (mut child, key) = spawn_sleeper(&mut sys);
+
+        let handle = ProcessHandle::open(&mut sys, key).expect("failed to open handle");
+        handle.send_signal(ProcessSignal::Term).unwrap();
+        assert!(handle.wait_for_exit(Duration::from_secs(5)));
+        child.wait().unwrap();
+    }
+
+    /// Tests that a stale start time is refused
+    #[cfg(unix)]
+    #[test]
+    fn test_stale_key_is_refused() {
+        let mut sys = System::new();
+        let (mut child, key) = spawn_sleeper(&mut sys);
+
+        let stale = ProcessKey {
+            pid: key.pid,
+            start_time: key.start_time.saturating_sub(3600),
+        };
+        assert!(ProcessHandle::open(&mut sys, stale).is_err());
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
@@ -0,0 +1,303 @@
+//! Process environment inspection
+//!
+//! Environment variables are no longer part of the bulk process list; this
+//! module reads them on demand for a single process, hides the values of
+//! variables whose names look like secrets, and compares the environments of
+//! two processes.
+
+use super::filters::glob_match;
+use super::settings_file;
+use super::{
+    EnvironmentChange, EnvironmentDiff, EnvironmentVariable, ProcessEnvironment, ProcessKey,
+    ProcessMonitor,
+};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::path::PathBuf;
+use sysinfo::ProcessExt;
+
+/// Placeholder shown instead of a masked value
+const MASKED_VALUE: &str = "********";
+
+/// Name patterns masked by default
+const DEFAULT_SECRET_PATTERNS: &[&str] = &[
+    "*TOKEN*",
+    "*SECRET*",
+    "*PASSWORD*",
+    "*PASSWD*",
+    "*API_KEY*",
+    "*APIKEY*",
+    "*ACCESS_KEY*",
+    "*PRIVATE_KEY*",
+    "*CREDENTIAL*",
+    "*AUTH_TOKEN*",
+    "*COOKIE*",
+    "*SESSION_KEY*",
+    "*SESSION_SECRET*",
+];
+
+/// Reads process environments and masks secrets
+#[derive(Debug)]
+pub struct EnvironmentInspector {
+    /// Case-insensitive glob patterns (`*` matches any run of characters)
+    secret_patterns: Vec<String>,
+    /// File the patterns are saved to, set once the app data directory is known
+    settings_path: Option<PathBuf>,
+}
+
+impl Default for EnvironmentInspector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EnvironmentInspector {
+    /// Creates an inspector with the default secret patterns
+    pub fn new() -> Self {
+        Self {
+            secret_patterns: DEFAULT_SECRET_PATTERNS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            settings_path: None,
+        }
+    }
+
+    /// Sets the file the patterns are saved to and loads any saved patterns
+    pub fn load_settings(&mut self, path: PathBuf) {
+        if let Some(patterns) = settings_file::load(&path) {
+            self.secret_patterns = patterns;
+        }
+        self.settings_path = Some(path);
+    }
+
+    /// Returns the patterns used to detect secret variable names
+    pub fn secret_patterns(&self) -> &[String] {
+        &self.secret_patterns
+    }
+
+    /// Replaces and saves the patterns used to detect secret variable names
+    ///
+    /// Blank patterns are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error string if the patterns cannot be saved; they are
+    /// applied regardless
+    pub fn set_secret_patterns(&mut self, patterns: Vec<String>) -> Result<(), String> {
+        self.secret_patterns = patterns
+            .into_iter()
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+        match &self.settings_path {
+            Some(path) => settings_file::save(path, &self.secret_patterns),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads the environment of a process
+    ///
+    /// # Arguments
+    ///
+    /// * `sys` - System information provider
+    /// * `key` - Identity of the process
+    ///
+    /// # Returns
+    ///
+    /// Variables sorted by name with secret values masked, or an error string
+    /// if the process no longer exists
+    pub fn environment(
+        &self,
+        sys: &mut sysinfo::System,
+        key: ProcessKey,
+    ) -> Result<ProcessEnvironment, String> {
+        let raw = Self::read_raw(sys, key)?;
+        Ok(ProcessEnvironment {
+            key,
+            variables: raw
+                .into_iter()
+                .map(|(name, value)| self.variable(name, value))
+                .collect(),
+        })
+    }
+
+    /// Compares the environments of two processes
+    ///
+    /// Values are compared unmasked, so a changed secret is reported as
+    /// changed even though both sides are shown masked.
+    ///
+    /// # Arguments
+    ///
+    /// * `sys` - System information provider
+    /// * `left` - Identity of the first process
+    /// * `right` - Identity of the second process
+    pub fn diff(
+        &self,
+        sys: &mut sysinfo::System,
+        left: ProcessKey,
+        right: ProcessKey,
+    ) -> Result<EnvironmentDiff, String> {
+        let left_env = Self::read_raw(sys, left)?;
+        let right_env = Self::read_raw(sys, right)?;
+        Ok(self.diff_variables((left, &left_env), (right, &right_env)))
+    }
+
+    /// Builds the difference between two parsed environments
+    fn diff_variables(
+        &self,
+        (left_key, left): (ProcessKey, &BTreeMap<String, String>),
+        (right_key, right): (ProcessKey, &BTreeMap<String, String>),
+    ) -> EnvironmentDiff {
+        let mut diff = EnvironmentDiff {
+            left: left_key,
+            right: right_key,
+            only_left: Vec::new(),
+            only_right: Vec::new(),
+            changed: Vec::new(),
+            unchanged: 0,
+        };
+
+        for (name, left_value) in left {
+            match right.get(name) {
+                None => diff
+                    .only_left
+                    .push(self.variable(name.clone(), left_value.clone())),
+                Some(right_value) if right_value == left_value => diff.unchanged += 1,
+                Some(right_value) => {
+                    let masked = self.is_secret(name);
+                    let shown = |value: &String| {
+                        if masked {
+                            MASKED_VALUE.to_string()
+                        } else {
+                            value.clone()
+                        }
+                    };
+                    diff.changed.push(EnvironmentChange {
+                        key: name.clone(),
+                        left: shown(left_value),
+                        right: shown(right_value),
+                        masked,
+                    });
+                }
+            }
+        }
+        diff.only_right = right
+            .iter()
+            .filter(|(name, _)| !left.contains_key(*name))
+            .map(|(name, value)| self.variable(name.clone(), value.clone()))
+            .collect();
+        diff
+    }
+
+    /// Reads and parses the raw `KEY=VALUE` entries of a process
+    fn read_raw(
+        sys: &mut sysinfo::System,
+        key: ProcessKey,
+    ) -> Result<BTreeMap<String, String>, String> {
+        let process = ProcessMonitor::find_process(sys, key)?;
+        Ok(Self::parse(process.environ()))
+    }
+
+    /// Splits `KEY=VALUE` entries; later duplicates win, as with `getenv`
+    fn parse(entries: &[String]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| match entry.split_once('=') {
+                Some((name, value)) => (name.to_string(), value.to_string()),
+                None => (entry.clone(), String::new()),
+            })
+            .collect()
+    }
+
+    /// Builds a variable, masking its value if the name looks like a secret
+    fn variable(&self, key: String, value: String) -> EnvironmentVariable {
+        let masked = self.is_secret(&key);
+        EnvironmentVariable {
+            value: if masked {
+                MASKED_VALUE.to_string()
+            } else {
+                value
+            },
+            key,
+            masked,
+        }
+    }
+
+    /// Checks a variable name against the secret patterns
+    fn is_secret(&self, name: &str) -> bool {
+        let name = name.to_ascii_uppercase();
+        self.secret_patterns
+            .iter()
+            .any(|pattern| glob_match(&pattern.to_ascii_uppercase(), &name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests parsing, masking and diffing of environments
+    #[test]
+    fn test_mask_and_diff() {
+        let inspector = EnvironmentInspector::new();
+        let left = EnvironmentInspector::parse(&[
+            "PATH=/usr/bin".to_string(),
+            "GITHUB_TOKEN=abc".to_string(),
+            "EQUATION=a=b".to_string(),
+            "HOME=/root".to_string(),
+        ]);
+        let right = EnvironmentInspector::parse(&[
+            "PATH=/usr/local/bin".to_string(),
+            "GITHUB_TOKEN=def".to_string(),
+            "HOME=/root".to_string(),
+            "LANG=C".to_string(),
+        ]);
+        assert_eq!(left["EQUATION"], "a=b");
+
+        let key = ProcessKey {
+            pid: 1,
+            start_time: 0,
+        };
+        let diff = inspector.diff_variables((key, &left), (key, &right));
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.only_left.len(), 1);
+        assert_eq!(diff.only_right[0].key, "LANG");
+        assert_eq!(diff.changed.len(), 2);
+        let token = diff
+            .changed
+            .iter()
+            .find(|c| c.key == "GITHUB_TOKEN")
+            .unwrap();
+        assert!(token.masked);
+        assert_eq!(token.left, MASKED_VALUE);
+    }
+
+    /// Tests that session and display variables stay visible and that
+    /// patterns survive a restart
+    #[test]
+    fn test_secret_patterns() {
+        let inspector = EnvironmentInspector::new();
+        for name in ["XAUTHORITY", "XDG_SESSION_ID", "DBUS_SESSION_BUS_ADDRESS"] {
+            assert!(!inspector.is_secret(name), "{} is masked", name);
+        }
+        assert!(inspector.is_secret("GITLAB_AUTH_TOKEN"));
+        assert!(inspector.is_secret("FLASK_SESSION_KEY"));
+
+        let path = std::env::temp_dir().join(format!(
+            "neohtop-secret-patterns-{}.json",
+            std::process::id()
+        ));
+        let mut inspector = EnvironmentInspector::new();
+        inspector.load_settings(path.clone());
+        inspector
+            .set_secret_patterns(vec!["MY_*".to_string(), " ".to_string()])
+            .unwrap();
+
+        let mut restarted = EnvironmentInspector::new();
+        restarted.load_settings(path.clone());
+        assert_eq!(restarted.secret_patterns(), ["MY_*"]);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,142 @@
+//! Per-process metric history
+//!
+//! Each live process keeps a rolling series of its CPU usage, memory usage
+//! and disk I/O rates, averaged into fixed-width buckets so an hour of
+//! history stays small enough to keep for every process.
+
+use super::ProcessSample;
+use std::collections::VecDeque;
+
+/// Width of a bucket in seconds
+pub const PROCESS_HISTORY_RESOLUTION: u64 = 10;
+
+/// Number of completed buckets kept per process (one hour)
+const PROCESS_HISTORY_CAPACITY: usize = 360;
+
+/// Rolling, downsampled metric history of one process
+#[derive(Clone, Debug, Default)]
+pub struct ProcessSeries {
+    /// Completed buckets, oldest first
+    samples: VecDeque<ProcessSample>,
+    /// Bucket still receiving samples
+    pending: Option<Bucket>,
+}
+
+impl ProcessSeries {
+    /// Adds one observation of the process
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - Time of the observation (Unix timestamp)
+    /// * `cpu_usage` - CPU usage as percentage
+    /// * `memory_usage` - Physical memory usage in bytes
+    /// * `disk_usage` - Bytes read and written since the previous observation
+    pub fn record(
+        &mut self,
+        timestamp: u64,
+        cpu_usage: f32,
+        memory_usage: u64,
+        disk_usage: (u64, u64),
+    ) {
+        let start = timestamp - timestamp % PROCESS_HISTORY_RESOLUTION;
+        if self
+            .pending
+            .as_ref()
+            .is_some_and(|bucket| bucket.start != start)
+        {
+            if let Some(bucket) = self.pending.take() {
+                if self.samples.len() == PROCESS_HISTORY_CAPACITY {
+                    self.samples.pop_front();
+                }
+                self.samples.push_back(bucket.average());
+            }
+        }
+        let bucket = self.pending.get_or_insert(Bucket {
+            start,
+            last: timestamp,
+            count: 0,
+            cpu_usage: 0.0,
+            memory_usage: 0.0,
+            disk_read_bytes: 0,
+            disk_written_bytes: 0,
+        });
+        bucket.last = timestamp;
+        bucket.count += 1;
+        bucket.cpu_usage += f64::from(cpu_usage);
+        bucket.memory_usage += memory_usage as f64;
+        bucket.disk_read_bytes += disk_usage.0;
+        bucket.disk_written_bytes += disk_usage.1;
+    }
+
+    /// Returns all samples, oldest first, including the bucket still being filled
+    pub fn samples(&self) -> Vec<ProcessSample> {
+        self.samples
+            .iter()
+            .cloned()
+            .chain(self.pending.as_ref().map(Bucket::average))
+            .collect()
+    }
+}
+
+/// Running sums of the observations falling into one bucket
+#[derive(Clone, Debug)]
+struct Bucket {
+    /// Start of the bucket (Unix timestamp)
+    start: u64,
+    /// Time of the latest observation (Unix timestamp)
+    last: u64,
+    /// Number of observations added
+    count: u32,
+    /// Sum of the CPU usage observations
+    cpu_usage: f64,
+    /// Sum of the memory usage observations
+    memory_usage: f64,
+    /// Bytes read during the bucket
+    disk_read_bytes: u64,
+    /// Bytes written during the bucket
+    disk_written_bytes: u64,
+}
+
+impl Bucket {
+    fn average(&self) -> ProcessSample {
+        let count = f64::from(self.count.max(1));
+        // The I/O counters cover the whole second of every observation
+        let seconds = (self.last - self.start + 1).min(PROCESS_HISTORY_RESOLUTION);
+        ProcessSample {
+            timestamp: self.start,
+            cpu_usage: (self.cpu_usage / count) as f32,
+            memory_usage: (self.memory_usage / count) as u64,
+            disk_read_bytes_per_sec: self.disk_read_bytes / seconds,
+            disk_written_bytes_per_sec: self.disk_written_bytes / seconds,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests averaging observations into buckets and the capacity limit
+    #[test]
+    fn test_process_series() {
+        let mut series = ProcessSeries::default();
+        series.record(1_000, 10.0, 100, (0, 0));
+        series.record(1_004, 30.0, 300, (5_000, 0));
+        series.record(1_009, 20.0, 200, (5_000, 1_000));
+        series.record(1_010, 50.0, 1_000, (0, 0));
+
+        let samples = series.samples();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].timestamp, 1_000);
+        assert_eq!(samples[0].cpu_usage, 20.0);
+        assert_eq!(samples[0].memory_usage, 200);
+        assert_eq!(samples[0].disk_read_bytes_per_sec, 1_000);
+        assert_eq!(samples[0].disk_written_bytes_per_sec, 100);
+        assert_eq!(samples[1].memory_usage, 1_000);
+
+        for bucket in 0..PROCESS_HISTORY_CAPACITY as u64 + 5 {
+            series.record(2_000 + bucket * PROCESS_HISTORY_RESOLUTION, 1.0, 1, (0, 0));
+        }
+        assert_eq!(series.samples().len(), PROCESS_HISTORY_CAPACITY + 1);
+    }
+}
@@ -0,0 +1,608 @@
+//! Process lifecycle tracking
+//!
+//! This module records fork, exec and exit events so that processes living
+//! shorter than a refresh interval are not invisible. On Linux it subscribes
+//! to the netlink proc connector; when that is unavailable (other platforms,
+//! or missing `CAP_NET_ADMIN`) it falls back to diffing consecutive process
+//! list refreshes.
+
+use super::{ProcessEvent, ProcessEventKind, ProcessEventSource};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Debug};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use sysinfo::{PidExt, ProcessExt, SystemExt};
+
+/// Maximum number of lifecycle events kept in memory
+const MAX_EVENTS: usize = 1000;
+
+/// Callback used to forward batches of new events to the frontend
+type EventEmitter = Arc<dyn Fn(&[ProcessEvent]) + Send + Sync>;
+
+/// Process identity remembered between refreshes for the polling fallback
+#[derive(Clone, Debug)]
+struct KnownProcess {
+    start_time: u64,
+    ppid: Option<u32>,
+    name: String,
+}
+
+/// Tracks process creation and termination
+pub struct ProcessLifecycleMonitor {
+    /// Bounded history of recent events, shared with the netlink reader thread
+    events: Arc<Mutex<VecDeque<ProcessEvent>>>,
+    /// Whether the netlink reader thread is currently delivering events
+    netlink_active: Arc<AtomicBool>,
+    /// Processes seen during the previous refresh
+    known: HashMap<u32, KnownProcess>,
+    /// Whether `known` has been populated by at least one refresh
+    initialized: bool,
+    /// Frontend notification callback
+    emitter: Option<EventEmitter>,
+}
+
+impl Debug for ProcessLifecycleMonitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProcessLifecycleMonitor")
+            .field("source", &self.source())
+            .field("known", &self.known.len())
+            .finish()
+    }
+}
+
+impl Default for ProcessLifecycleMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProcessLifecycleMonitor {
+    /// Creates a new lifecycle monitor in polling mode
+    pub fn new() -> Self {
+        Self {
+            events: Arc::new(Mutex::new(VecDeque::with_capacity(MAX_EVENTS))),
+            netlink_active: Arc::new(AtomicBool::new(false)),
+            known: HashMap::new(),
+            initialized: false,
+            emitter: None,
+        }
+    }
+
+    /// Starts event delivery
+    ///
+    /// Attempts to subscribe to the netlink proc connector and silently keeps
+    /// using the polling fallback if that fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `emit` - Callback invoked with every batch of newly observed events
+    pub fn start<F>(&mut self, emit: F)
+    where
+        F: Fn(&[ProcessEvent]) + Send + Sync + 'static,
+    {
+        let emitter: EventEmitter = Arc::new(emit);
+        self.emitter = Some(emitter.clone());
+
+        #[cfg(target_os = "linux")]
+        if let Ok(socket) = netlink::ProcConnector::connect() {
+            self.netlink_active.store(true, Ordering::SeqCst);
+            let events = self.events.clone();
+            let active = self.netlink_active.clone();
+            let spawned = std::thread::Builder::new()
+                .name("process-lifecycle".into())
+                .spawn(move || {
+                    netlink::run(socket, |batch| {
+                        Self::push_events(&events, batch);
+                        emitter(batch);
+                    });
+                    active.store(false, Ordering::SeqCst);
+                });
+            if spawned.is_err() {
+                self.netlink_active.store(false, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Returns the mechanism currently producing events
+    pub fn source(&self) -> ProcessEventSource {
+        if self.netlink_active.load(Ordering::SeqCst) {
+            ProcessEventSource::Netlink
+        } else {
+            ProcessEventSource::Polling
+        }
+    }
+
+    /// Compares the current process list against the previous refresh
+    ///
+    /// Records and emits the differences as events when the netlink
+    /// connector is not delivering them already.
+    ///
+    /// # Arguments
+    ///
+    /// * `sys` - System information provider, already refreshed
+    pub fn observe(&mut self, sys: &sysinfo::System) {
+        let current: HashMap<u32, KnownProcess> = sys
+            .processes()
+            .iter()
+            .map(|(pid, process)| {
+                (
+                    pid.as_u32(),
+                    KnownProcess {
+                        start_time: process.start_time(),
+                        ppid: process.parent().map(|p| p.as_u32()),
+                        name: process.name().to_string(),
+                    },
+                )
+            })
+            .collect();
+
+        let previous = std::mem::replace(&mut self.known, current);
+        if !self.initialized {
+            self.initialized = true;
+            return;
+        }
+        if self.source() == ProcessEventSource::Netlink {
+            return;
+        }
+
+        let batch = Self::diff_snapshots(&previous, &self.known, Self::now_millis());
+        if batch.is_empty() {
+            return;
+        }
+        Self::push_events(&self.events, &batch);
+        if let Some(emit) = &self.emitter {
+            emit(&batch);
+        }
+    }
+
+    /// Returns the most recent events, newest last
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum number of events to return
+    pub fn recent_events(&self, limit: usize) -> Vec<ProcessEvent> {
+        let events = match self.events.lock() {
+            Ok(events) => events,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let skip = events.len().saturating_sub(limit);
+        events.iter().skip(skip).cloned().collect()
+    }
+
+    /// Builds polling events from two consecutive snapshots
+    fn diff_snapshots(
+        previous: &HashMap<u32, KnownProcess>,
+        current: &HashMap<u32, KnownProcess>,
+        timestamp: u64,
+    ) -> Vec<ProcessEvent> {
+        let polling_event = |kind, pid, info: &KnownProcess| ProcessEvent {
+            kind,
+            pid,
+            ppid: info.ppid,
+            name: Some(info.name.clone()),
+            timestamp,
+            exit_code: None,
+            exit_signal: None,
+            source: ProcessEventSource::Polling,
+        };
+
+        let mut events: Vec<ProcessEvent> = previous
+            .iter()
+            .filter(|(pid, old)| {
+                !matches!(current.get(pid), Some(new) if new.start_time == old.start_time)
+            })
+            .map(|(pid, old)| polling_event(ProcessEventKind::Exit, *pid, old))
+            .collect();
+
+        events.extend(
+            current
+                .iter()
+                .filter(|(pid, new)| {
+                    !matches!(previous.get(pid), Some(old) if old.start_time == new.start_time)
+                })
+                .map(|(pid, new)| polling_event(ProcessEventKind::Fork, *pid, new)),
+        );
+        events
+    }
+
+    /// Appends events to the shared history, dropping the oldest on overflow
+    fn push_events(events: &Mutex<VecDeque<ProcessEvent>>, batch: &[ProcessEvent]) {
+        let mut events = match events.lock() {
+            Ok(events) => events,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        for event in batch {
+            if events.len() == MAX_EVENTS {
+                events.pop_front();
+            }
+            events.push_back(event.clone());
+        }
+    }
+
+    /// Gets the current system time in milliseconds since UNIX epoch
+    fn now_millis() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+/// Linux netlink proc connector client
+#[cfg(target_os = "linux")]
+mod netlink {
+    use super::{ProcessEvent, ProcessEventKind, ProcessEventSource, ProcessLifecycleMonitor};
+    use std::io;
+    use std::mem;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::time::{Duration, Instant};
+
+    /// Connector index and value of the process events connector
+    const CN_IDX_PROC: u32 = 1;
+    const CN_VAL_PROC: u32 = 1;
+    /// Connector operation subscribing to process events
+    const PROC_CN_MCAST_LISTEN: u32 = 1;
+
+    /// `proc_event.what` values we care about
+    const PROC_EVENT_FORK: u32 = 0x0000_0001;
+    const PROC_EVENT_EXEC: u32 = 0x0000_0002;
+    const PROC_EVENT_EXIT: u32 = 0x8000_0000;
+
+    /// Sizes of `struct nlmsghdr` and `struct cn_msg`
+    const NLMSG_HDR_LEN: usize = 16;
+    const CN_MSG_LEN: usize = 20;
+    /// Offset of `event_data` inside `struct proc_event`
+    const PROC_EVENT_DATA_OFFSET: usize = 16;
+
+    /// Netlink control message types that carry no connector payload
+    const NLMSG_NOOP: u16 = 1;
+    const NLMSG_ERROR: u16 = 2;
+    /// Message type the kernel uses for connector payloads
+    const NLMSG_DONE: u16 = 3;
+
+    /// Maximum delay before buffered events are flushed to the frontend
+    const FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+    /// Subscribed netlink connector socket
+    pub struct ProcConnector {
+        fd: OwnedFd,
+    }
+
+    impl ProcConnector {
+        /// Opens the connector socket and subscribes to process events
+        ///
+        /// Fails without `CAP_NET_ADMIN` or on kernels built without
+        /// `CONFIG_PROC_EVENTS`.
+        pub fn connect() -> io::Result<Self> {
+            // SAFETY: plain socket(2) call; the returned descriptor is checked
+            // and immediately wrapped in an OwnedFd.
+            let raw = unsafe {
+                libc::socket(
+                    libc::AF_NETLINK,
+                    libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+                    libc::NETLINK_CONNECTOR,
+                )
+            };
+            if raw < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            // SAFETY: `raw` is a freshly created, valid descriptor we own.
+            let fd = unsafe { OwnedFd::from_raw_fd(raw) };
+
+            // SAFETY: sockaddr_nl is plain old data, so all-zero is valid.
+            let mut addr: libc::sockaddr_nl = unsafe { mem::zeroed() };
+            addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+            addr.nl_pid = std::process::id();
+            addr.nl_groups = CN_IDX_PROC;
+            // SAFETY: `addr` is a properly initialised sockaddr_nl and the
+            // length passed matches its size.
+            let bound = unsafe {
+                libc::bind(
+                    fd.as_raw_fd(),
+                    &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+                    mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+                )
+            };
+            if bound < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            let connector = Self { fd };
+            connector.send(&subscribe_message(std::process::id()))?;
+            connector.set_receive_timeout(FLUSH_INTERVAL)?;
+            Ok(connector)
+        }
+
+        fn send(&self, message: &[u8]) -> io::Result<()> {
+            // SAFETY: the pointer and length describe a valid, live slice.
+            let sent = unsafe {
+                libc::send(
+                    self.fd.as_raw_fd(),
+                    message.as_ptr() as *const libc::c_void,
+                    message.len(),
+                    0,
+                )
+            };
+            if sent < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        fn set_receive_timeout(&self, timeout: Duration) -> io::Result<()> {
+            let tv = libc::timeval {
+                tv_sec: timeout.as_secs() as libc::time_t,
+                tv_usec: timeout.subsec_micros() as libc::suseconds_t,
+            };
+            // SAFETY: `tv` is a valid timeval and the length matches its size.
+            let result = unsafe {
+                libc::setsockopt(
+                    self.fd.as_raw_fd(),
+                    libc::SOL_SOCKET,
+                    libc::SO_RCVTIMEO,
+                    &tv as *const libc::timeval as *const libc::c_void,
+                    mem::size_of::<libc::timeval>() as libc::socklen_t,
+                )
+            };
+            if result < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+            // SAFETY: the pointer and length describe a valid, writable slice.
+            let received = unsafe {
+                libc::recv(
+                    self.fd.as_raw_fd(),
+                    buf.as_mut_ptr() as *mut libc::c_void,
+                    buf.len(),
+                    0,
+                )
+            };
+            if received < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(received as usize)
+        }
+    }
+
+    /// Reads events until the socket fails, flushing them in batches
+    pub fn run<F>(socket: ProcConnector, mut flush: F)
+    where
+        F: FnMut(&[ProcessEvent]),
+    {
+        let mut buf = vec![0u8; 8192];
+        let mut pending = Vec::new();
+        let mut last_flush = Instant::now();
+
+        loop {
+            match socket.recv(&mut buf) {
+                Ok(len) => {
+                    let timestamp = ProcessLifecycleMonitor::now_millis();
+                    pending.extend(parse_messages(&buf[..len], timestamp));
+                }
+                Err(e) => match e.kind() {
+                    io::ErrorKind::WouldBlock
+                    | io::ErrorKind::TimedOut
+                    | io::ErrorKind::Interrupted => {}
+                    // ENOBUFS: the kernel dropped events because we fell
+                    // behind; keep reading rather than giving up.
+                    _ if e.raw_os_error() == Some(libc::ENOBUFS) => {}
+                    _ => break,
+                },
+            }
+
+            if !pending.is_empty() && last_flush.elapsed() >= FLUSH_INTERVAL {
+                flush(&pending);
+                pending.clear();
+                last_flush = Instant::now();
+            }
+        }
+
+        if !pending.is_empty() {
+            flush(&pending);
+        }
+    }
+
+    /// Builds the `PROC_CN_MCAST_LISTEN` subscription message
+    fn subscribe_message(port_id: u32) -> Vec<u8> {
+        let total_len = NLMSG_HDR_LEN + CN_MSG_LEN + mem::size_of::<u32>();
+        let mut msg = Vec::with_capacity(total_len);
+        // struct nlmsghdr
+        msg.extend_from_slice(&(total_len as u32).to_ne_bytes());
+        msg.extend_from_slice(&NLMSG_DONE.to_ne_bytes());
+        msg.extend_from_slice(&0u16.to_ne_bytes());
+        msg.extend_from_slice(&0u32.to_ne_bytes());
+        msg.extend_from_slice(&port_id.to_ne_bytes());
+        // struct cn_msg
+        msg.extend_from_slice(&CN_IDX_PROC.to_ne_bytes());
+        msg.extend_from_slice(&CN_VAL_PROC.to_ne_bytes());
+        msg.extend_from_slice(&0u32.to_ne_bytes());
+        msg.extend_from_slice(&0u32.to_ne_bytes());
+        msg.extend_from_slice(&(mem::size_of::<u32>() as u16).to_ne_bytes());
+        msg.extend_from_slice(&0u16.to_ne_bytes());
+        // enum proc_cn_mcast_op
+        msg.extend_from_slice(&PROC_CN_MCAST_LISTEN.to_ne_bytes());
+        msg
+    }
+
+    fn read_u16(buf: &[u8], offset: usize) -> Option<u16> {
+        buf.get(offset..offset + 2)
+            .map(|b| u16::from_ne_bytes([b[0], b[1]]))
+    }
+
+    fn read_u32(buf: &[u8], offset: usize) -> Option<u32> {
+        buf.get(offset..offset + 4)
+            .map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// Parses every netlink message in a datagram into process events
+    ///
+    /// Thread-level fork and exit events are dropped so that the feed only
+    /// reports whole processes.
+    pub(super) fn parse_messages(buf: &[u8], timestamp: u64) -> Vec<ProcessEvent> {
+        let mut events = Vec::new();
+        let mut offset = 0;
+
+        while let (Some(len), Some(kind)) = (read_u32(buf, offset), read_u16(buf, offset + 4)) {
+            let len = len as usize;
+            if len < NLMSG_HDR_LEN || offset + len > buf.len() {
+                break;
+            }
+            if kind != NLMSG_NOOP && kind != NLMSG_ERROR {
+                let payload = &buf[offset + NLMSG_HDR_LEN..offset + len];
+                if let Some(event) = parse_proc_event(payload, timestamp) {
+                    events.push(event);
+                }
+            }
+            // Netlink messages are 4-byte aligned
+            offset += (len + 3) & !3;
+        }
+        events
+    }
+
+    /// Parses a `cn_msg` carrying a `proc_event`
+    fn parse_proc_event(payload: &[u8], timestamp: u64) -> Option<ProcessEvent> {
+        if read_u32(payload, 0)? != CN_IDX_PROC || read_u32(payload, 4)? != CN_VAL_PROC {
+            return None;
+        }
+        let event = payload.get(CN_MSG_LEN..)?;
+        let what = read_u32(event, 0)?;
+        let data = event.get(PROC_EVENT_DATA_OFFSET..)?;
+
+        let base = |kind, pid, ppid| ProcessEvent {
+            kind,
+            pid,
+            ppid,
+            name: None,
+            timestamp,
+            exit_code: None,
+            exit_signal: None,
+            source: ProcessEventSource::Netlink,
+        };
+
+        match what {
+            PROC_EVENT_FORK => {
+                let parent_tgid = read_u32(data, 4)?;
+                let child_pid = read_u32(data, 8)?;
+                let child_tgid = read_u32(data, 12)?;
+                (child_pid == child_tgid).then(|| {
+                    let mut event = base(ProcessEventKind::Fork, child_tgid, Some(parent_tgid));
+                    event.name = read_comm(child_tgid);
+                    event
+                })
+            }
+            PROC_EVENT_EXEC => {
+                let process_tgid = read_u32(data, 4)?;
+                let mut event = base(ProcessEventKind::Exec, process_tgid, None);
+                event.name = read_comm(process_tgid);
+                Some(event)
+            }
+            PROC_EVENT_EXIT => {
+                let process_pid = read_u32(data, 0)?;
+                let process_tgid = read_u32(data, 4)?;
+                if process_pid != process_tgid {
+                    return None;
+                }
+                // `exit_code` holds the wait(2) status of the process
+                let status = read_u32(data, 8)?;
+                let mut event = base(ProcessEventKind::Exit, process_tgid, read_u32(data, 20));
+                if status & 0x7f == 0 {
+                    event.exit_code = Some(((status >> 8) & 0xff) as i32);
+                } else {
+                    event.exit_signal = Some((status & 0x7f) as i32);
+                }
+                Some(event)
+            }
+            _ => None,
+        }
+    }
+
+    /// Reads a process name, which may already be gone for short-lived processes
+    fn read_comm(pid: u32) -> Option<String> {
+        std::fs::read_to_string(format!("/proc/{}/comm", pid))
+            .ok()
+            .map(|comm| comm.trim_end().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn known(start_time: u64) -> KnownProcess {
+        KnownProcess {
+            start_time,
+            ppid: Some(1),
+            name: "test".to_string(),
+        }
+    }
+
+    /// Tests that polling diffs detect new, exited and reused PIDs
+    #[test]
+    fn test_diff_snapshots() {
+        let previous = HashMap::from([(10, known(100)), (11, known(100))]);
+        let current = HashMap::from([(10, known(100)), (11, known(200)), (12, known(300))]);
+
+        let events = ProcessLifecycleMonitor::diff_snapshots(&previous, &current, 0);
+        let count = |kind, pid| {
+            events
+                .iter()
+                .filter(|e| e.kind == kind && e.pid == pid)
+                .count()
+        };
+        assert_eq!(events.len(), 3);
+        assert_eq!(count(ProcessEventKind::Exit, 11), 1);
+        assert_eq!(count(ProcessEventKind::Fork, 11), 1);
+        assert_eq!(count(ProcessEventKind::Fork, 12), 1);
+    }
+
+    /// Builds a connector datagram carrying a single proc_event
+    #[cfg(target_os = "linux")]
+    fn proc_event_message(what: u32, data: &[u32]) -> Vec<u8> {
+        let payload_len = 16 + data.len() * 4;
+        let total_len = 16 + 20 + payload_len;
+        let mut msg = Vec::new();
+        msg.extend_from_slice(&(total_len as u32).to_ne_bytes());
+        msg.extend_from_slice(&3u16.to_ne_bytes());
+        msg.extend_from_slice(&[0; 10]);
+        msg.extend_from_slice(&1u32.to_ne_bytes());
+        msg.extend_from_slice(&1u32.to_ne_bytes());
+        msg.extend_from_slice(&[0; 8]);
+        msg.extend_from_slice(&(payload_len as u16).to_ne_bytes());
+        msg.extend_from_slice(&[0; 2]);
+        msg.extend_from_slice(&what.to_ne_bytes());
+        msg.extend_from_slice(&[0; 12]);
+        for value in data {
+            msg.extend_from_slice(&value.to_ne_bytes());
+        }
+        msg
+    }
+
+    /// Tests decoding of netlink exit statuses and thread filtering
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_netlink_events() {
+        const FORK: u32 = 0x0000_0001;
+        const EXIT: u32 = 0x8000_0000;
+
+        let mut buf = proc_event_message(EXIT, &[4242, 4242, 1 << 8, 17, 1, 1]);
+        buf.extend(proc_event_message(EXIT, &[4243, 4243, 9, 17, 1, 1]));
+        // Thread exit and thread creation must be ignored
+        buf.extend(proc_event_message(EXIT, &[4244, 4242, 0, 17, 1, 1]));
+        buf.extend(proc_event_message(FORK, &[10, 10, 21, 20]));
+        buf.extend(proc_event_message(FORK, &[10, 10, 20, 20]));
+
+        let events = netlink::parse_messages(&buf, 0);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].kind, ProcessEventKind::Exit);
+        assert_eq!(events[0].exit_code, Some(1));
+        assert_eq!(events[0].ppid, Some(1));
+        assert_eq!(events[1].exit_code, None);
+        assert_eq!(events[1].exit_signal, Some(9));
+        assert_eq!(events[2].kind, ProcessEventKind::Fork);
+        assert_eq!((events[2].pid, events[2].ppid), (20, Some(10)));
+    }
+}
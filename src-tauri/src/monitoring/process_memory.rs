@@ -0,0 +1,123 @@
+//! Detailed process memory accounting
+//!
+//! The process list only carries the resident set size reported by sysinfo.
+//! This module reads `/proc/<pid>/smaps_rollup` on demand for a single
+//! process, which adds the proportional set size, the shared/private split,
+//! swap usage and how much of the process is backed by huge pages.
+
+use super::{ProcessKey, ProcessMemoryDetails, ProcessMonitor};
+
+/// Reads the memory accounting of a process
+///
+/// # Arguments
+///
+/// * `sys` - System information provider
+/// * `key` - Identity of the process
+///
+/// # Errors
+///
+/// Returns an error string if the process no longer exists, its PID was
+/// reused, or its `smaps_rollup` cannot be read
+#[cfg(target_os = "linux")]
+pub fn read_process_memory_details(
+    sys: &mut sysinfo::System,
+    key: ProcessKey,
+) -> Result<ProcessMemoryDetails, String> {
+    ProcessMonitor::find_process(sys, key)?;
+    let content = std::fs::read_to_string(format!("/proc/{}/smaps_rollup", key.pid))
+        .map_err(|e| format!("Failed to read memory map of process {}: {}", key.pid, e))?;
+    Ok(parse_smaps_rollup(key, &content))
+}
+
+/// Reads the memory accounting of a process
+#[cfg(not(target_os = "linux"))]
+pub fn read_process_memory_details(
+    sys: &mut sysinfo::System,
+    key: ProcessKey,
+) -> Result<ProcessMemoryDetails, String> {
+    ProcessMonitor::find_process(sys, key)?;
+    Err("Detailed memory accounting is only available on Linux".to_string())
+}
+
+/// Reads the proportional set size of a process without verifying its identity
+///
+/// Meant for bulk use on a fresh process list, where the cost of re-checking
+/// every start time outweighs the small chance of a PID being reused.
+///
+/// # Returns
+///
+/// The PSS in bytes, or `None` if the memory map cannot be read, e.g. for
+/// another user's process without root privileges
+#[cfg(target_os = "linux")]
+pub fn read_process_pss(key: ProcessKey) -> Option<u64> {
+    let content = std::fs::read_to_string(format!("/proc/{}/smaps_rollup", key.pid)).ok()?;
+    Some(parse_smaps_rollup(key, &content).pss)
+}
+
+/// Reads the proportional set size of a process
+#[cfg(not(target_os = "linux"))]
+pub fn read_process_pss(_key: ProcessKey) -> Option<u64> {
+    None
+}
+
+/// Parses the `Key: value kB` lines of `smaps_rollup`
+#[cfg(target_os = "linux")]
+fn parse_smaps_rollup(key: ProcessKey, content: &str) -> ProcessMemoryDetails {
+    let fields: std::collections::HashMap<&str, u64> = content
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            let kb: u64 = value.split_whitespace().next()?.parse().ok()?;
+            Some((name.trim(), kb * 1024))
+        })
+        .collect();
+    let field = |name: &str| fields.get(name).copied().unwrap_or(0);
+
+    ProcessMemoryDetails {
+        key,
+        rss: field("Rss"),
+        pss: field("Pss"),
+        pss_anon: field("Pss_Anon"),
+        pss_file: field("Pss_File"),
+        pss_shmem: field("Pss_Shmem"),
+        shared: field("Shared_Clean") + field("Shared_Dirty"),
+        private: field("Private_Clean") + field("Private_Dirty"),
+        swap: field("Swap"),
+        anon_hugepages: field("AnonHugePages"),
+        shmem_hugepages: field("ShmemPmdMapped"),
+        file_hugepages: field("FilePmdMapped"),
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    /// Tests parsing of smaps_rollup
+    #[test]
+    fn test_parse_smaps_rollup() {
+        let key = ProcessKey {
+            pid: 42,
+            start_time: 1_700_000_000,
+        };
+        let details = parse_smaps_rollup(
+            key,
+            "55d0c0a4e000-7ffd4b1f2000 ---p 00000000 00:00 0                  [rollup]\n\
+             Rss:              204800 kB\n\
+             Pss:              150000 kB\n\
+             Pss_Anon:         120000 kB\n\
+             Shared_Clean:      60000 kB\n\
+             Shared_Dirty:       4800 kB\n\
+             Private_Clean:     20000 kB\n\
+             Private_Dirty:    120000 kB\n\
+             AnonHugePages:     81920 kB\n\
+             Swap:                512 kB\n",
+        );
+        assert_eq!(details.key, key);
+        assert_eq!(details.rss, 204_800 * 1024);
+        assert_eq!(details.pss, 150_000 * 1024);
+        assert_eq!(details.shared + details.private, details.rss);
+        assert_eq!(details.anon_hugepages, 81_920 * 1024);
+        assert_eq!(details.pss_file, 0);
+    }
+}
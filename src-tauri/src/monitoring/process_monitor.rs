@@ -3,17 +3,41 @@
 //! This module handles monitoring and managing system processes, including
 //! collecting process information and managing process lifecycle.
 
-use super::{ProcessData, ProcessInfo, ProcessStaticInfo};
-use std::collections::HashMap;
+use super::process_history::{ProcessSeries, PROCESS_HISTORY_RESOLUTION};
+use super::{
+    ExitedProcess, ProcessData, ProcessEvent, ProcessEventKind, ProcessHistory, ProcessInfo,
+    ProcessKey, ProcessStaticInfo, ProcessTracker,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::Debug;
 use std::time::{SystemTime, UNIX_EPOCH};
 use sysinfo::{PidExt, ProcessExt, ProcessStatus, SystemExt};
 
+/// Maximum number of exited processes remembered
+const MAX_EXITED_PROCESSES: usize = 200;
+
+/// How long the metric history of an exited process is kept, in seconds
+const EXITED_HISTORY_RETENTION: u64 = 300;
+
 /// Monitors and manages system processes
 #[derive(Debug)]
 pub struct ProcessMonitor {
     /// Cache for static process information to avoid redundant allocations
-    process_cache: HashMap<u32, ProcessStaticInfo>,
+    process_cache: HashMap<ProcessKey, ProcessStaticInfo>,
+    /// Running statistics of processes seen during the last refresh
+    trackers: HashMap<ProcessKey, ProcessTracker>,
+    /// Recently exited processes, oldest first
+    exited_processes: VecDeque<ExitedProcess>,
+    /// Process list produced by the last refresh
+    last_processes: Vec<ProcessInfo>,
+    /// Metric histories of recently exited processes with their exit time, oldest first
+    exited_histories: VecDeque<(ProcessKey, u64, ProcessSeries)>,
+}
+
+impl Default for ProcessMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ProcessMonitor {
@@ -21,6 +45,10 @@ impl ProcessMonitor {
     pub fn new() -> Self {
         Self {
             process_cache: HashMap::new(),
+            trackers: HashMap::new(),
+            exited_processes: VecDeque::with_capacity(MAX_EXITED_PROCESSES),
+            last_processes: Vec::new(),
+            exited_histories: VecDeque::new(),
         }
     }
 
@@ -36,23 +64,153 @@ impl ProcessMonitor {
     pub fn collect_processes(&mut self, sys: &sysinfo::System) -> Result<Vec<ProcessInfo>, String> {
         let current_time = Self::get_current_time()?;
         let processes_data = self.collect_process_data(sys, current_time);
-        Ok(self.build_process_info(processes_data))
+        let processes = self.build_process_info(processes_data, current_time);
+        self.last_processes = processes.clone();
+        Ok(processes)
+    }
+
+    /// Tracks a process list read back from a capture instead of the system
+    ///
+    /// The list goes through the same tracking as a live collection, so a
+    /// replay can show process histories and exits as of its position.
+    ///
+    /// # Arguments
+    ///
+    /// * `processes` - The replayed process list
+    /// * `current_time` - Time of the snapshot in seconds since the Unix epoch
+    pub fn replay_processes(&mut self, processes: &[ProcessInfo], current_time: u64) {
+        let processes_data = processes
+            .iter()
+            .map(|process| {
+                let total_disk_usage = self
+                    .trackers
+                    .get(&process.key())
+                    .map_or((0, 0), |tracker| tracker.total_disk_usage);
+                ProcessData {
+                    pid: process.pid,
+                    name: process.name.clone(),
+                    cmd: vec![process.command.clone()],
+                    exe: process.executable.clone(),
+                    user_id: Some(process.user.clone()),
+                    cpu_usage: process.cpu_usage,
+                    memory: process.memory_usage,
+                    status: Self::parse_status(&process.status),
+                    ppid: Some(process.ppid),
+                    root: process.root.clone(),
+                    virtual_memory: process.virtual_memory,
+                    start_time: process.start_time,
+                    run_time: process.run_time,
+                    disk_usage: sysinfo::DiskUsage {
+                        read_bytes: process.disk_usage.0,
+                        written_bytes: process.disk_usage.1,
+                        total_read_bytes: total_disk_usage.0 + process.disk_usage.0,
+                        total_written_bytes: total_disk_usage.1 + process.disk_usage.1,
+                    },
+                    session_id: process.session_id,
+                }
+            })
+            .collect();
+        self.last_processes = self.build_process_info(processes_data, current_time);
+    }
+
+    /// Returns the process list produced by the last call to `collect_processes`
+    ///
+    /// Views derived from the process list use this snapshot rather than
+    /// refreshing again, so their CPU usage covers the same interval as the
+    /// table the user is looking at.
+    pub fn last_processes(&self) -> &[ProcessInfo] {
+        &self.last_processes
     }
 
-    /// Attempts to kill a process
+    /// Returns recently exited processes, most recent first
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - Lifecycle events used to fill in exit codes and exact exit times
+    pub fn exited_processes(&self, events: &[ProcessEvent]) -> Vec<ExitedProcess> {
+        self.exited_processes
+            .iter()
+            .rev()
+            .map(|process| {
+                let mut process = process.clone();
+                let exit_event = events.iter().rev().find(|event| {
+                    event.kind == ProcessEventKind::Exit
+                        && event.pid == process.pid
+                        && event.timestamp / 1000 >= process.start_time
+                        && event.timestamp / 1000 <= process.exit_time
+                });
+                if let Some(event) = exit_event {
+                    process.exit_code = event.exit_code;
+                    process.exit_signal = event.exit_signal;
+                    process.exit_time = event.timestamp / 1000;
+                    process.lifetime = process.exit_time.saturating_sub(process.start_time);
+                }
+                process
+            })
+            .collect()
+    }
+
+    /// Returns the metric history of a running or recently exited process
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Identity of the process
+    ///
+    /// # Returns
+    ///
+    /// The history, or `None` if the process was never seen or exited too
+    /// long ago
+    pub fn process_history(&self, key: ProcessKey) -> Option<ProcessHistory> {
+        let (exit_time, series) = match self.trackers.get(&key) {
+            Some(tracker) => (None, &tracker.history),
+            None => self
+                .exited_histories
+                .iter()
+                .find(|(exited, _, _)| *exited == key)
+                .map(|(_, exit_time, series)| (Some(*exit_time), series))?,
+        };
+        Some(ProcessHistory {
+            key,
+            resolution: PROCESS_HISTORY_RESOLUTION,
+            exit_time,
+            samples: series.samples(),
+        })
+    }
+
+    /// Looks up a live process by its stable identity
+    ///
+    /// The process is refreshed first, and only returned if it still has the
+    /// start time recorded in `key`.
+    ///
+    /// Pass a scratch `System`, not the one the process list is collected
+    /// with: refreshing a process restarts the interval sysinfo measures its
+    /// CPU usage over.
     ///
     /// # Arguments
     ///
     /// * `sys` - System information provider
-    /// * `pid` - Process ID to kill
+    /// * `key` - Identity of the process
     ///
     /// # Returns
     ///
-    /// Boolean indicating whether the process was successfully killed
-    pub fn kill_process(sys: &sysinfo::System, pid: u32) -> bool {
-        sys.process(sysinfo::Pid::from(pid as usize))
-            .map(|process| process.kill())
-            .unwrap_or(false)
+    /// The process, or an error string if it no longer exists or its PID now
+    /// belongs to a different process
+    pub fn find_process(
+        sys: &mut sysinfo::System,
+        key: ProcessKey,
+    ) -> Result<&sysinfo::Process, String> {
+        let pid = sysinfo::Pid::from(key.pid as usize);
+        if !sys.refresh_process(pid) {
+            return Err(format!("Process {} no longer exists", key.pid));
+        }
+        match sys.process(pid) {
+            Some(process) if process.start_time() == key.start_time => Ok(process),
+            Some(_) => Err(format!(
+                "Process {} has exited and its PID was reused by another process",
+                key.pid
+            )),
+            None => Err(format!("Process {} no longer exists", key.pid)),
+        }
     }
 
     /// Gets the current system time in seconds since UNIX epoch
@@ -73,12 +231,12 @@ impl ProcessMonitor {
                     pid: pid.as_u32(),
                     name: process.name().to_string(),
                     cmd: process.cmd().to_vec(),
+                    exe: process.exe().to_string_lossy().into_owned(),
                     user_id: process.user_id().map(|uid| uid.to_string()),
                     cpu_usage: process.cpu_usage(),
                     memory: process.memory(),
                     status: process.status(),
                     ppid: process.parent().map(|p| p.as_u32()),
-                    environ: process.environ().to_vec(),
                     root: process.root().to_string_lossy().into_owned(),
                     virtual_memory: process.virtual_memory(),
                     start_time,
@@ -95,16 +253,23 @@ impl ProcessMonitor {
     }
 
     /// Builds process information from raw process data
-    fn build_process_info(&mut self, processes: Vec<ProcessData>) -> Vec<ProcessInfo> {
-        processes
+    fn build_process_info(
+        &mut self,
+        processes: Vec<ProcessData>,
+        current_time: u64,
+    ) -> Vec<ProcessInfo> {
+        let infos = processes
             .into_iter()
             .map(|data| {
+                self.track_process(&data, current_time);
+
                 let cached_info =
                     self.process_cache
-                        .entry(data.pid)
+                        .entry(data.key())
                         .or_insert_with(|| ProcessStaticInfo {
                             name: data.name.clone(),
                             command: data.cmd.join(" "),
+                            executable: data.exe.clone(),
                             user: data.user_id.unwrap_or_else(|| "-".to_string()),
                         });
 
@@ -117,8 +282,8 @@ impl ProcessMonitor {
                     status: Self::format_status(data.status),
                     user: cached_info.user.clone(),
                     command: cached_info.command.clone(),
+                    executable: cached_info.executable.clone(),
                     threads: None,
-                    environ: data.environ,
                     root: data.root,
                     virtual_memory: data.virtual_memory,
                     start_time: data.start_time,
@@ -127,7 +292,101 @@ impl ProcessMonitor {
                     session_id: data.session_id,
                 }
             })
-            .collect()
+            .collect::<Vec<_>>();
+
+        let live: HashSet<ProcessKey> = infos.iter().map(ProcessInfo::key).collect();
+        self.record_exits(&live, current_time);
+        infos
+    }
+
+    /// Updates the running statistics of a live process
+    fn track_process(&mut self, data: &ProcessData, current_time: u64) {
+        let tracker = self
+            .trackers
+            .entry(data.key())
+            .or_insert_with(|| ProcessTracker {
+                ppid: data.ppid.unwrap_or(0),
+                cpu_usage: 0.0,
+                memory: 0,
+                peak_cpu_usage: 0.0,
+                peak_memory: 0,
+                total_disk_usage: (0, 0),
+                history: ProcessSeries::default(),
+            });
+        tracker.ppid = data.ppid.unwrap_or(0);
+        tracker.cpu_usage = data.cpu_usage;
+        tracker.memory = data.memory;
+        tracker.peak_cpu_usage = tracker.peak_cpu_usage.max(data.cpu_usage);
+        tracker.peak_memory = tracker.peak_memory.max(data.memory);
+        tracker.total_disk_usage = (
+            data.disk_usage.total_read_bytes,
+            data.disk_usage.total_written_bytes,
+        );
+        tracker.history.record(
+            current_time,
+            data.cpu_usage,
+            data.memory,
+            (data.disk_usage.read_bytes, data.disk_usage.written_bytes),
+        );
+    }
+
+    /// Moves processes that disappeared since the last refresh into the exited list
+    ///
+    /// Also evicts their cached static information and expired histories. A
+    /// PID that was reused by a new process counts as an exit of the previous
+    /// owner.
+    fn record_exits(&mut self, live: &HashSet<ProcessKey>, current_time: u64) {
+        while self
+            .exited_histories
+            .front()
+            .is_some_and(|(_, exit_time, _)| exit_time + EXITED_HISTORY_RETENTION < current_time)
+        {
+            self.exited_histories.pop_front();
+        }
+
+        let gone: Vec<ProcessKey> = self
+            .trackers
+            .keys()
+            .filter(|key| !live.contains(key))
+            .copied()
+            .collect();
+
+        for key in gone {
+            let Some(tracker) = self.trackers.remove(&key) else {
+                continue;
+            };
+            let static_info = self.process_cache.remove(&key);
+            let (name, command, user) = static_info
+                .map(|info| (info.name, info.command, info.user))
+                .unwrap_or_else(|| (String::new(), String::new(), "-".to_string()));
+
+            if self.exited_histories.len() == MAX_EXITED_PROCESSES {
+                self.exited_histories.pop_front();
+            }
+            self.exited_histories
+                .push_back((key, current_time, tracker.history));
+
+            if self.exited_processes.len() == MAX_EXITED_PROCESSES {
+                self.exited_processes.pop_front();
+            }
+            self.exited_processes.push_back(ExitedProcess {
+                pid: key.pid,
+                ppid: tracker.ppid,
+                name,
+                command,
+                user,
+                start_time: key.start_time,
+                exit_time: current_time,
+                lifetime: current_time.saturating_sub(key.start_time),
+                cpu_usage: tracker.cpu_usage,
+                memory_usage: tracker.memory,
+                peak_cpu_usage: tracker.peak_cpu_usage,
+                peak_memory_usage: tracker.peak_memory,
+                disk_usage: tracker.total_disk_usage,
+                exit_code: None,
+                exit_signal: None,
+            });
+        }
     }
 
     /// Formats process status into a human-readable string
@@ -140,11 +399,22 @@ impl ProcessMonitor {
         }
         .to_string()
     }
+
+    /// Reverses `format_status` for replayed processes
+    fn parse_status(status: &str) -> ProcessStatus {
+        match status {
+            "Running" => ProcessStatus::Run,
+            "Sleeping" => ProcessStatus::Sleep,
+            "Idle" => ProcessStatus::Idle,
+            _ => ProcessStatus::Unknown(0),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::monitoring::ProcessEventSource;
     use sysinfo::System;
 
     /// Tests creation of a new process monitor
@@ -164,4 +434,90 @@ mod tests {
         let result = monitor.collect_processes(&sys);
         assert!(result.is_ok());
     }
+
+    fn process_data(pid: u32, cpu_usage: f32, memory: u64) -> ProcessData {
+        ProcessData {
+            pid,
+            name: format!("proc-{}", pid),
+            cmd: vec![],
+            exe: String::new(),
+            user_id: None,
+            cpu_usage,
+            memory,
+            status: ProcessStatus::Run,
+            ppid: Some(1),
+            root: String::new(),
+            virtual_memory: 0,
+            start_time: 100,
+            run_time: 0,
+            disk_usage: Default::default(),
+            session_id: None,
+        }
+    }
+
+    /// Tests that vanished processes are remembered with their peaks and evicted from the cache
+    #[test]
+    fn test_exited_process_tracking() {
+        let mut monitor = ProcessMonitor::new();
+        monitor.build_process_info(
+            vec![process_data(10, 80.0, 500), process_data(11, 1.0, 1)],
+            110,
+        );
+        monitor.build_process_info(
+            vec![process_data(10, 5.0, 900), process_data(11, 1.0, 1)],
+            120,
+        );
+        monitor.build_process_info(vec![process_data(11, 1.0, 1)], 130);
+
+        let exit_event = ProcessEvent {
+            kind: ProcessEventKind::Exit,
+            pid: 10,
+            ppid: Some(1),
+            name: None,
+            timestamp: 125_000,
+            exit_code: None,
+            exit_signal: Some(9),
+            source: ProcessEventSource::Netlink,
+        };
+        let exited = monitor.exited_processes(&[exit_event]);
+        assert_eq!(exited.len(), 1);
+        assert_eq!(exited[0].name, "proc-10");
+        assert_eq!(exited[0].peak_cpu_usage, 80.0);
+        assert_eq!(exited[0].peak_memory_usage, 900);
+        assert_eq!(exited[0].memory_usage, 900);
+        assert_eq!(exited[0].exit_signal, Some(9));
+        assert_eq!(exited[0].lifetime, 25);
+        assert!(monitor.process_cache.keys().all(|key| key.pid != 10));
+        assert!(monitor.process_cache.keys().any(|key| key.pid == 11));
+
+        let key = process_data(10, 0.0, 0).key();
+        let history = monitor.process_history(key).unwrap();
+        assert_eq!(history.exit_time, Some(130));
+        assert_eq!(history.samples.len(), 2);
+        assert_eq!(history.samples[1].memory_usage, 900);
+
+        monitor.build_process_info(vec![process_data(11, 1.0, 1)], 130 + 301);
+        assert!(monitor.process_history(key).is_none());
+    }
+
+    /// Tests that a reused PID does not inherit the previous owner's identity
+    #[test]
+    fn test_pid_reuse() {
+        let mut monitor = ProcessMonitor::new();
+        monitor.build_process_info(vec![process_data(10, 1.0, 1)], 110);
+
+        let mut reused = process_data(10, 1.0, 1);
+        reused.name = "other".to_string();
+        reused.start_time = 200;
+        let infos = monitor.build_process_info(vec![reused], 210);
+
+        assert_eq!(infos[0].name, "other");
+        assert_eq!(monitor.process_cache.len(), 1);
+        let exited = monitor.exited_processes(&[]);
+        assert_eq!(exited.len(), 1);
+        assert_eq!(
+            (exited[0].name.as_str(), exited[0].start_time),
+            ("proc-10", 100)
+        );
+    }
 }
@@ -0,0 +1,47 @@
+//! Helpers for reading Linux procfs and sysfs files
+//!
+//! Most pseudo files hold a single value or a simple `key value` table; these
+//! helpers keep the individual collectors free of repetitive parsing code.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Reads a pseudo file and trims the trailing newline
+pub fn read_string(path: impl AsRef<Path>) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|content| content.trim().to_string())
+}
+
+/// Reads a pseudo file holding a single unsigned integer
+pub fn read_u64(path: impl AsRef<Path>) -> Option<u64> {
+    read_string(path)?.parse().ok()
+}
+
+/// Parses a whitespace separated `key value` table such as `/proc/vmstat`
+pub fn parse_key_values(content: &str) -> HashMap<&str, u64> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let key = parts.next()?;
+            let value = parts.next()?.parse().ok()?;
+            Some((key, value))
+        })
+        .collect()
+}
+
+/// Parses a kernel CPU list such as `0-3,8,10-11`
+pub fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.trim()
+        .split(',')
+        .filter(|range| !range.is_empty())
+        .flat_map(|range| {
+            let (start, end) = range.split_once('-').unwrap_or((range, range));
+            match (start.parse::<usize>(), end.parse::<usize>()) {
+                (Ok(start), Ok(end)) => start..end + 1,
+                _ => 0..0,
+            }
+        })
+        .collect()
+}
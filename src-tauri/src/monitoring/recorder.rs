@@ -0,0 +1,407 @@
+//! Persistent metrics recording
+//!
+//! When enabled, the recorder appends a condensed sample of the system
+//! statistics and the busiest processes to a JSON Lines file in the app data
+//! directory, one file per UTC day. Old files are deleted once they fall
+//! outside the retention period or the recordings exceed the size limit; the
+//! file of the current day is always kept. Samples are taken whenever
+//! statistics are collected, including by the background sampler, so
+//! nothing is recorded while NeoHtop is not running.
+
+use super::settings_file;
+use super::{
+    MetricsSample, ProcessInfo, RecordedProcess, RecordedSample, RecorderConfig, RecorderStatus,
+    SystemStats,
+};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Milliseconds in a day
+const DAY_MS: u64 = 86_400_000;
+
+/// Maximum number of samples returned by a single query
+const MAX_QUERY_SAMPLES: usize = 10_000;
+
+/// Writes samples to disk and reads them back
+#[derive(Debug)]
+pub struct MetricsRecorder {
+    /// Current settings
+    config: RecorderConfig,
+    /// Directory holding the recordings, set once the app data directory is known
+    directory: Option<PathBuf>,
+    /// File the settings are saved to, set once the app data directory is known
+    settings_path: Option<PathBuf>,
+    /// Time of the last written sample in milliseconds since the Unix epoch
+    last_written: Option<u64>,
+    /// Error of the last failed write, cleared by the next successful one
+    last_error: Option<String>,
+}
+
+impl Default for MetricsRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsRecorder {
+    /// Creates a disabled recorder without a directory
+    pub fn new() -> Self {
+        Self {
+            config: RecorderConfig::default(),
+            directory: None,
+            settings_path: None,
+            last_written: None,
+            last_error: None,
+        }
+    }
+
+    /// Sets the directory the recordings are stored in
+    pub fn set_directory(&mut self, directory: PathBuf) {
+        self.directory = Some(directory);
+    }
+
+    /// Returns the directory the recordings are stored in, once known
+    pub fn directory(&self) -> Option<&Path> {
+        self.directory.as_deref()
+    }
+
+    /// Sets the file the settings are saved to and loads any saved settings
+    pub fn load_settings(&mut self, path: PathBuf) {
+        if let Some(config) = settings_file::load::<RecorderConfig>(&path) {
+            if config.interval_secs > 0 {
+                self.config = config;
+            }
+        }
+        self.settings_path = Some(path);
+    }
+
+    /// Returns the settings, storage location and health of the recorder
+    pub fn status(&self) -> RecorderStatus {
+        RecorderStatus {
+            config: self.config.clone(),
+            directory: self
+                .directory
+                .as_ref()
+                .map(|directory| directory.to_string_lossy().into_owned()),
+            size_bytes: self
+                .directory
+                .as_deref()
+                .map(|directory| {
+                    list_recordings(directory)
+                        .iter()
+                        .map(|(_, _, size)| size)
+                        .sum()
+                })
+                .unwrap_or(0),
+            last_error: self.last_error.clone(),
+        }
+    }
+
+    /// Replaces and saves the settings and applies the new retention limits
+    ///
+    /// # Errors
+    ///
+    /// Returns an error string if the sampling interval is zero, the
+    /// settings cannot be saved or old recordings cannot be deleted
+    pub fn set_config(&mut self, config: RecorderConfig) -> Result<(), String> {
+        if config.interval_secs == 0 {
+            return Err("The recording interval must be at least one second".to_string());
+        }
+        self.config = config;
+        if let Some(path) = &self.settings_path {
+            settings_file::save(path, &self.config)?;
+        }
+        match &self.directory {
+            Some(directory) => prune(directory, &self.config, now_millis()),
+            None => Ok(()),
+        }
+    }
+
+    /// Records a sample if enabled and the interval has elapsed
+    ///
+    /// Failures are kept for `status` instead of being returned, so a full
+    /// disk never interrupts the live view.
+    ///
+    /// # Arguments
+    ///
+    /// * `stats` - The statistics just collected
+    /// * `processes` - The process list just collected
+    pub fn record(&mut self, stats: &SystemStats, processes: &[ProcessInfo]) {
+        let now = now_millis();
+        let interval_ms = self.config.interval_secs * 1000;
+        let recent =
+            matches!(self.last_written, Some(last) if now.saturating_sub(last) < interval_ms);
+        if !self.config.enabled || recent {
+            return;
+        }
+        let Some(directory) = &self.directory else {
+            return;
+        };
+
+        let mut top: Vec<&ProcessInfo> = processes.iter().collect();
+        top.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+        let sample = RecordedSample {
+            metrics: MetricsSample::from_stats(now, stats),
+            load_avg: stats.load_avg,
+            top_processes: top
+                .into_iter()
+                .take(self.config.top_processes)
+                .map(|process| RecordedProcess {
+                    pid: process.pid,
+                    name: process.name.clone(),
+                    user: process.user.clone(),
+                    cpu_usage: process.cpu_usage,
+                    memory_usage: process.memory_usage,
+                })
+                .collect(),
+        };
+
+        let result = append(directory, &sample).and_then(|()| prune(directory, &self.config, now));
+        self.last_written = Some(now);
+        self.last_error = result.err();
+    }
+}
+
+/// Appends a sample to the file of its day
+fn append(directory: &Path, sample: &RecordedSample) -> Result<(), String> {
+    std::fs::create_dir_all(directory)
+        .map_err(|e| format!("Failed to create {}: {}", directory.display(), e))?;
+    let path = directory.join(file_name(sample.metrics.timestamp / DAY_MS));
+    let mut line = serde_json::to_string(sample).map_err(|e| e.to_string())?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Reads the recorded samples between two points in time
+///
+/// Long ranges are thinned out evenly to at most 10000 samples. This is a
+/// free function so callers can read files without holding the recorder.
+///
+/// # Arguments
+///
+/// * `directory` - Directory holding the recordings
+/// * `start` - Start of the range in milliseconds since the Unix epoch
+/// * `end` - End of the range in milliseconds since the Unix epoch
+///
+/// # Errors
+///
+/// Returns an error string if a recording cannot be read
+pub fn read_recordings(
+    directory: &Path,
+    start: u64,
+    end: u64,
+) -> Result<Vec<RecordedSample>, String> {
+    let (first_day, last_day) = (start / DAY_MS, end / DAY_MS);
+    let mut samples = Vec::new();
+    for (day, path, _) in list_recordings(directory) {
+        if day < first_day || day > last_day {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        // A line cut short by a crash is skipped rather than failing the query
+        samples.extend(
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str::<RecordedSample>(line).ok())
+                .filter(|sample| {
+                    sample.metrics.timestamp >= start && sample.metrics.timestamp <= end
+                }),
+        );
+    }
+    if samples.len() > MAX_QUERY_SAMPLES {
+        let stride = samples.len().div_ceil(MAX_QUERY_SAMPLES);
+        samples = samples.into_iter().step_by(stride).collect();
+    }
+    Ok(samples)
+}
+
+/// Deletes recordings outside the retention period, then the oldest ones
+/// until the total size fits the limit, always keeping the current day's file
+fn prune(directory: &Path, config: &RecorderConfig, now: u64) -> Result<(), String> {
+    let today = now / DAY_MS;
+    let oldest_day = today.saturating_sub(config.retention_days.saturating_sub(1));
+    let mut total: u64 = list_recordings(directory)
+        .iter()
+        .map(|(_, _, size)| size)
+        .sum();
+    for (day, path, size) in list_recordings(directory) {
+        if day >= today || (day >= oldest_day && total <= config.max_bytes) {
+            break;
+        }
+        std::fs::remove_file(&path)
+            .map_err(|e| format!("Failed to delete {}: {}", path.display(), e))?;
+        total -= size;
+    }
+    Ok(())
+}
+
+/// Lists the recording files as (day since the Unix epoch, path, size), oldest first
+fn list_recordings(directory: &Path) -> Vec<(u64, PathBuf, u64)> {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(u64, PathBuf, u64)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let day = parse_file_name(&name)?;
+            let size = entry.metadata().ok()?.len();
+            Some((day, entry.path(), size))
+        })
+        .collect();
+    files.sort_by_key(|(day, _, _)| *day);
+    files
+}
+
+/// Returns the file name for a day, e.g. `metrics-2024-03-01.jsonl`
+fn file_name(day: u64) -> String {
+    let (year, month, day) = civil_from_days(day);
+    format!("metrics-{:04}-{:02}-{:02}.jsonl", year, month, day)
+}
+
+/// Extracts the day since the Unix epoch from a recording file name
+fn parse_file_name(name: &str) -> Option<u64> {
+    let date = name.strip_prefix("metrics-")?.strip_suffix(".jsonl")?;
+    let mut parts = date.splitn(3, '-').map(|part| part.parse::<u64>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    let day_number = days_from_civil(year, month, day);
+    (civil_from_days(day_number) == (year, month, day)).then_some(day_number)
+}
+
+/// Converts days since the Unix epoch to a Gregorian (year, month, day)
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    // Howard Hinnant's algorithm, restricted to dates after 1970
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Converts a Gregorian date to days since the Unix epoch
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = year - u64::from(month <= 2);
+    let era = year / 400;
+    let yoe = year % 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day.saturating_sub(1);
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    (era * 146_097 + doe).saturating_sub(719_468)
+}
+
+/// Gets the current system time in milliseconds since UNIX epoch
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp: u64) -> RecordedSample {
+        RecordedSample {
+            metrics: MetricsSample {
+                timestamp,
+                cpu_usage: vec![12.5],
+                memory_used: 1 << 30,
+                swap_used: 0,
+                network_rx_bytes: 100,
+                network_tx_bytes: 50,
+                disk_read_bytes: 0,
+                disk_write_bytes: 4096,
+            },
+            load_avg: [0.5, 0.25, 0.1],
+            top_processes: vec![RecordedProcess {
+                pid: 42,
+                name: "postgres".to_string(),
+                user: "999".to_string(),
+                cpu_usage: 12.0,
+                memory_usage: 1 << 20,
+            }],
+        }
+    }
+
+    /// Tests that settings survive a restart
+    #[test]
+    fn test_recorder_settings() {
+        let path = std::env::temp_dir().join(format!(
+            "neohtop-recorder-settings-{}.json",
+            std::process::id()
+        ));
+        let mut recorder = MetricsRecorder::new();
+        recorder.load_settings(path.clone());
+        let config = RecorderConfig {
+            enabled: true,
+            interval_secs: 30,
+            ..RecorderConfig::default()
+        };
+        recorder.set_config(config).unwrap();
+
+        let mut restarted = MetricsRecorder::new();
+        restarted.load_settings(path.clone());
+        assert!(restarted.status().config.enabled);
+        assert_eq!(restarted.status().config.interval_secs, 30);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    /// Tests converting between days and calendar dates
+    #[test]
+    fn test_file_names() {
+        assert_eq!(file_name(0), "metrics-1970-01-01.jsonl");
+        assert_eq!(file_name(19_782), "metrics-2024-02-29.jsonl");
+        assert_eq!(parse_file_name("metrics-2024-02-29.jsonl"), Some(19_782));
+        assert_eq!(parse_file_name("metrics-2023-02-29.jsonl"), None);
+        assert_eq!(parse_file_name("recorder.json"), None);
+    }
+
+    /// Tests writing, querying and pruning recordings
+    #[test]
+    fn test_record_query_prune() {
+        let directory =
+            std::env::temp_dir().join(format!("neohtop-recorder-{}", std::process::id()));
+        let day = 19_782 * DAY_MS;
+        for timestamp in [day - 1000, day + 3_600_000, day + 7_200_000, day + DAY_MS] {
+            append(&directory, &sample(timestamp)).unwrap();
+        }
+
+        let samples = read_recordings(&directory, day, day + 5_000_000).unwrap();
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0], sample(day + 3_600_000));
+        assert_eq!(read_recordings(&directory, 0, u64::MAX).unwrap().len(), 4);
+
+        let config = RecorderConfig {
+            retention_days: 2,
+            ..RecorderConfig::default()
+        };
+        prune(&directory, &config, day + DAY_MS).unwrap();
+        assert_eq!(list_recordings(&directory).len(), 2);
+
+        let config = RecorderConfig {
+            max_bytes: 1,
+            ..config
+        };
+        prune(&directory, &config, day + DAY_MS).unwrap();
+        let remaining = list_recordings(&directory);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].0, day / DAY_MS + 1);
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
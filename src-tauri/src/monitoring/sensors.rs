@@ -0,0 +1,271 @@
+//! Hardware sensor readings
+//!
+//! This module reads temperatures, fan speeds, voltages, currents and power
+//! draw from the hwmon drivers and thermal zones exposed in sysfs. Other
+//! platforms fall back to the temperature components reported by sysinfo.
+
+#[cfg(target_os = "linux")]
+use super::{procfs, SensorKind};
+use super::{Sensor, SensorStatus};
+#[cfg(target_os = "linux")]
+use std::path::Path;
+
+/// Reads every available sensor
+#[cfg(target_os = "linux")]
+pub fn read_sensors() -> Vec<Sensor> {
+    let mut sensors = read_hwmon(Path::new("/sys/class/hwmon"));
+    sensors.extend(read_thermal_zones(Path::new("/sys/class/thermal")));
+    sensors
+}
+
+/// Reads every available sensor
+///
+/// # Arguments
+///
+/// * `sys` - System information provider
+#[cfg(not(target_os = "linux"))]
+pub fn read_sensors(sys: &mut sysinfo::System) -> Vec<Sensor> {
+    use super::SensorKind;
+    use sysinfo::{ComponentExt, SystemExt};
+
+    sys.refresh_components_list();
+    sys.components()
+        .iter()
+        .map(|component| {
+            let value = component.temperature() as f64;
+            let critical = component.critical().map(|c| c as f64);
+            Sensor {
+                device: String::new(),
+                label: component.label().to_string(),
+                kind: SensorKind::Temperature,
+                value,
+                // sysinfo reports the highest temperature seen, not a threshold
+                max: None,
+                critical,
+                status: status(value, None, critical),
+            }
+        })
+        .collect()
+}
+
+/// Compares a reading with its high and critical thresholds
+fn status(value: f64, max: Option<f64>, critical: Option<f64>) -> SensorStatus {
+    if critical.is_some_and(|critical| value >= critical) {
+        SensorStatus::Critical
+    } else if max.is_some_and(|max| value >= max) {
+        SensorStatus::High
+    } else {
+        SensorStatus::Normal
+    }
+}
+
+/// Reads the inputs of every hwmon chip
+#[cfg(target_os = "linux")]
+fn read_hwmon(root: &Path) -> Vec<Sensor> {
+    let Ok(chips) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut sensors = Vec::new();
+    for chip in chips.flatten() {
+        let path = chip.path();
+        if is_thermal_zone_mirror(&path) {
+            continue;
+        }
+        let device = procfs::read_string(path.join("name"))
+            .unwrap_or_else(|| chip.file_name().to_string_lossy().into_owned());
+        let Ok(files) = std::fs::read_dir(&path) else {
+            continue;
+        };
+        let mut chip_sensors: Vec<Sensor> = files
+            .flatten()
+            .filter_map(|file| {
+                let name = file.file_name().into_string().ok()?;
+                read_hwmon_input(&path, &device, &name)
+            })
+            .collect();
+        chip_sensors.sort_by(|a, b| a.label.cmp(&b.label));
+        sensors.extend(chip_sensors);
+    }
+    sensors.sort_by(|a, b| a.device.cmp(&b.device));
+    sensors
+}
+
+/// Checks whether a hwmon chip only mirrors a thermal zone
+///
+/// Thermal zones register a hwmon chip of their own. Those are skipped, so
+/// each zone is listed once, with its trip points, by `read_thermal_zones`.
+#[cfg(target_os = "linux")]
+fn is_thermal_zone_mirror(chip: &Path) -> bool {
+    std::fs::canonicalize(chip.join("device")).is_ok_and(|device| {
+        device
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with("thermal_zone"))
+    })
+}
+
+/// Reads one hwmon input such as `temp1_input` together with its label and limits
+#[cfg(target_os = "linux")]
+fn read_hwmon_input(chip: &Path, device: &str, file: &str) -> Option<Sensor> {
+    // Power meters expose an instantaneous reading, an averaged one or both;
+    // the averaged one is only used when there is no instantaneous one
+    let prefix = file.strip_suffix("_input").or_else(|| {
+        file.strip_suffix("_average")
+            .filter(|p| p.starts_with("power") && !chip.join(format!("{}_input", p)).exists())
+    })?;
+    let channel = prefix.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    let (kind, scale) = match &prefix[..prefix.len() - channel.len()] {
+        "temp" => (SensorKind::Temperature, 1_000.0),
+        "fan" => (SensorKind::Fan, 1.0),
+        "in" => (SensorKind::Voltage, 1_000.0),
+        "curr" => (SensorKind::Current, 1_000.0),
+        "power" => (SensorKind::Power, 1_000_000.0),
+        _ => return None,
+    };
+    // sysfs values are integers in milli- or micro-units
+    let read = |suffix: &str| {
+        procfs::read_string(chip.join(format!("{}_{}", prefix, suffix)))
+            .and_then(|value| value.parse::<i64>().ok())
+            .map(|value| value as f64 / scale)
+    };
+    let value = procfs::read_string(chip.join(file))?.parse::<i64>().ok()? as f64 / scale;
+    let (max, critical) = (read("max"), read("crit"));
+    Some(Sensor {
+        device: device.to_string(),
+        label: procfs::read_string(chip.join(format!("{}_label", prefix)))
+            .unwrap_or_else(|| prefix.to_string()),
+        kind,
+        value,
+        max,
+        critical,
+        status: status(value, max, critical),
+    })
+}
+
+/// Reads the temperature and trip points of every thermal zone
+#[cfg(target_os = "linux")]
+fn read_thermal_zones(root: &Path) -> Vec<Sensor> {
+    let Ok(zones) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut sensors: Vec<Sensor> = zones
+        .flatten()
+        .filter(|zone| {
+            zone.file_name()
+                .to_string_lossy()
+                .starts_with("thermal_zone")
+        })
+        .filter_map(|zone| {
+            let path = zone.path();
+            let millidegrees = |file: String| {
+                procfs::read_string(path.join(file))?
+                    .parse::<i64>()
+                    .ok()
+                    .map(|value| value as f64 / 1_000.0)
+            };
+            let trips: Vec<(String, f64)> = (0..)
+                .map_while(|trip| {
+                    procfs::read_string(path.join(format!("trip_point_{}_type", trip)))
+                        .map(|kind| (trip, kind))
+                })
+                .filter_map(|(trip, kind)| {
+                    Some((kind, millidegrees(format!("trip_point_{}_temp", trip))?))
+                })
+                .collect();
+            // The lowest trip point of a kind is the one reached first
+            let lowest = |kinds: &[&str]| {
+                trips
+                    .iter()
+                    .filter(|(kind, _)| kinds.contains(&kind.as_str()))
+                    .map(|(_, temp)| *temp)
+                    .reduce(f64::min)
+            };
+            let value = millidegrees("temp".to_string())?;
+            let max = lowest(&["passive"]);
+            let critical = lowest(&["hot", "critical"]);
+            Some(Sensor {
+                device: procfs::read_string(path.join("type"))?,
+                label: zone.file_name().to_string_lossy().into_owned(),
+                kind: SensorKind::Temperature,
+                value,
+                max,
+                critical,
+                status: status(value, max, critical),
+            })
+        })
+        .collect();
+    sensors.sort_by(|a, b| a.label.cmp(&b.label));
+    sensors
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    /// Tests reading hwmon inputs and thermal zones from a fake sysfs tree
+    #[test]
+    fn test_read_sysfs_sensors() {
+        let root = std::env::temp_dir().join(format!("neohtop-sensors-{}", std::process::id()));
+        let chip = root.join("hwmon/hwmon0");
+        let zone = root.join("thermal/thermal_zone0");
+        std::fs::create_dir_all(&chip).unwrap();
+        std::fs::create_dir_all(&zone).unwrap();
+        for (file, content) in [
+            ("name", "coretemp"),
+            ("temp1_input", "54000"),
+            ("temp1_label", "Package id 0"),
+            ("temp1_crit", "100000"),
+            ("fan2_input", "1200"),
+            ("power1_average", "15250000"),
+            ("power2_input", "8000000"),
+            ("power2_average", "7500000"),
+            ("in0_max", "1500"),
+        ] {
+            std::fs::write(chip.join(file), content).unwrap();
+        }
+        for (file, content) in [
+            ("type", "x86_pkg_temp"),
+            ("temp", "55000"),
+            ("trip_point_0_type", "passive"),
+            ("trip_point_0_temp", "90000"),
+            ("trip_point_1_type", "critical"),
+            ("trip_point_1_temp", "105000"),
+        ] {
+            std::fs::write(zone.join(file), content).unwrap();
+        }
+
+        // The hwmon mirror of the thermal zone is left to read_thermal_zones
+        let mirror = root.join("hwmon/hwmon1");
+        std::fs::create_dir_all(&mirror).unwrap();
+        std::fs::write(mirror.join("name"), "acpitz").unwrap();
+        std::fs::write(mirror.join("temp1_input"), "55000").unwrap();
+        std::os::unix::fs::symlink(&zone, mirror.join("device")).unwrap();
+
+        let sensors = read_hwmon(&root.join("hwmon"));
+        assert_eq!(sensors.len(), 4);
+        let package = sensors.iter().find(|s| s.label == "Package id 0").unwrap();
+        assert_eq!(package.value, 54.0);
+        assert_eq!(package.critical, Some(100.0));
+        assert_eq!(package.status, SensorStatus::Normal);
+        let fan = sensors.iter().find(|s| s.label == "fan2").unwrap();
+        assert!(matches!(fan.kind, SensorKind::Fan));
+        let power = sensors.iter().find(|s| s.label == "power1").unwrap();
+        assert_eq!(power.value, 15.25);
+        let power = sensors.iter().find(|s| s.label == "power2").unwrap();
+        assert_eq!(power.value, 8.0);
+
+        let zones = read_thermal_zones(&root.join("thermal"));
+        assert_eq!(zones[0].device, "x86_pkg_temp");
+        assert_eq!(zones[0].max, Some(90.0));
+        assert_eq!(zones[0].critical, Some(105.0));
+        assert_eq!(
+            status(95.0, zones[0].max, zones[0].critical),
+            SensorStatus::High
+        );
+        assert_eq!(
+            status(105.0, Some(90.0), Some(105.0)),
+            SensorStatus::Critical
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,373 @@
+//! Login session records
+//!
+//! This module reads the utmp database, which records who is logged in on
+//! which terminal. The file is a flat array of fixed-size `struct utmp`
+//! records; parsing it directly avoids the non-reentrant `getutxent` API.
+//! Only the glibc layout is parsed. Where utmp is unavailable or empty, as on
+//! musl or on systems without utmp support, the sessions tracked by
+//! systemd-logind are read instead. Each login is matched to the processes
+//! running in its session. Numeric user IDs are resolved to login names
+//! through the system user database.
+
+use super::{LoginSession, ProcessInfo};
+use std::collections::HashSet;
+#[cfg(target_os = "linux")]
+use std::path::Path;
+
+/// Location of the utmp database
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+const UTMP_PATH: &str = "/var/run/utmp";
+
+/// Size of one `struct utmp` record with glibc on Linux
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+const UTMP_RECORD_SIZE: usize = 384;
+
+/// `ut_type` of a record describing a logged in user
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+const USER_PROCESS: i16 = 7;
+
+/// Directory holding one state file per systemd-logind session
+#[cfg(target_os = "linux")]
+const LOGIND_SESSIONS_PATH: &str = "/run/systemd/sessions";
+
+/// A user logged in on a terminal
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoginRecord {
+    /// Login name
+    pub user: String,
+    /// Terminal, e.g. "pts/0" or "tty1"
+    pub terminal: String,
+    /// Remote host for network logins
+    pub host: Option<String>,
+    /// PID of the login process
+    pub pid: u32,
+    /// Session ID recorded by the login program, 0 if not set
+    pub session: u32,
+    /// Login time as a Unix timestamp
+    pub login_time: u64,
+}
+
+/// Reads the users currently logged in
+///
+/// Falls back to the logind sessions when utmp lists no logins.
+#[cfg(target_os = "linux")]
+pub fn read_login_records() -> Vec<LoginRecord> {
+    let records = read_utmp();
+    if records.is_empty() {
+        read_logind_sessions(Path::new(LOGIND_SESSIONS_PATH))
+    } else {
+        records
+    }
+}
+
+/// Reads the users currently logged in
+#[cfg(not(target_os = "linux"))]
+pub fn read_login_records() -> Vec<LoginRecord> {
+    Vec::new()
+}
+
+/// Resolves a numeric user ID to its login name
+///
+/// Uses `getpwuid_r`, so users from LDAP or other NSS sources are found too.
+///
+/// # Returns
+///
+/// The login name, or `None` if `uid` is not numeric or has no user entry
+#[cfg(target_os = "linux")]
+pub fn user_name(uid: &str) -> Option<String> {
+    let uid: libc::uid_t = uid.parse().ok()?;
+    let mut buffer: Vec<libc::c_char> = vec![0; 1024];
+    // SAFETY: passwd is plain old data; an all-zero value is valid.
+    let mut entry: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    loop {
+        // SAFETY: every pointer is valid for the call and `buffer.len()` is
+        // the size of the buffer the strings are written to.
+        let error = unsafe {
+            libc::getpwuid_r(
+                uid,
+                &mut entry,
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                &mut result,
+            )
+        };
+        if error != libc::ERANGE || buffer.len() >= 1 << 20 {
+            break;
+        }
+        buffer.resize(buffer.len() * 2, 0);
+    }
+    if result.is_null() {
+        return None;
+    }
+    // SAFETY: on success pw_name points to a NUL terminated string in `buffer`.
+    let name = unsafe { std::ffi::CStr::from_ptr(entry.pw_name) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+/// Resolves a numeric user ID to its login name
+#[cfg(not(target_os = "linux"))]
+pub fn user_name(_uid: &str) -> Option<String> {
+    None
+}
+
+/// Lists logged in users with the processes of their sessions
+///
+/// # Arguments
+///
+/// * `processes` - The current process list
+///
+/// # Returns
+///
+/// One entry per login, oldest first
+pub fn read_login_sessions(processes: &[ProcessInfo]) -> Vec<LoginSession> {
+    let mut sessions = build_sessions(read_login_records(), processes);
+    sessions.sort_by_key(|session| session.login_time);
+    sessions
+}
+
+/// Attributes processes to the login records they belong to
+///
+/// A process belongs to a login if its session ID is the one recorded in
+/// utmp, or is led by the login process or one of its direct children. The
+/// latter covers sshd and login(1), which record their own PID while the
+/// shell they spawn starts the new session.
+fn build_sessions(records: Vec<LoginRecord>, processes: &[ProcessInfo]) -> Vec<LoginSession> {
+    records
+        .into_iter()
+        .map(|record| {
+            let mut session_ids: HashSet<u32> = HashSet::from([record.pid]);
+            if record.session != 0 {
+                session_ids.insert(record.session);
+            }
+            session_ids.extend(
+                processes
+                    .iter()
+                    .filter(|process| {
+                        process.ppid == record.pid && process.session_id == Some(process.pid)
+                    })
+                    .map(|process| process.pid),
+            );
+            let members: Vec<&ProcessInfo> = processes
+                .iter()
+                .filter(|process| {
+                    process
+                        .session_id
+                        .is_some_and(|id| session_ids.contains(&id))
+                })
+                .collect();
+
+            LoginSession {
+                user: record.user,
+                terminal: record.terminal,
+                host: record.host,
+                login_time: record.login_time,
+                pid: record.pid,
+                process_count: members.len(),
+                cpu_usage: members.iter().map(|process| process.cpu_usage).sum(),
+                memory_usage: members.iter().map(|process| process.memory_usage).sum(),
+                processes: members.iter().map(|process| process.key()).collect(),
+            }
+        })
+        .collect()
+}
+
+/// Reads the logins recorded in utmp
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn read_utmp() -> Vec<LoginRecord> {
+    std::fs::read(UTMP_PATH)
+        .map(|content| parse_utmp(&content))
+        .unwrap_or_default()
+}
+
+/// Reads the logins recorded in utmp
+///
+/// The record layout differs from glibc's, so utmp is left to the logind
+/// fallback.
+#[cfg(all(target_os = "linux", not(target_env = "gnu")))]
+fn read_utmp() -> Vec<LoginRecord> {
+    Vec::new()
+}
+
+/// Parses the user process records of a utmp file
+///
+/// Layout: `ut_type` (i16 + padding), `ut_pid` (i32), `ut_line[32]`,
+/// `ut_id[4]`, `ut_user[32]`, `ut_host[256]`, `ut_exit` (2 x i16),
+/// `ut_session` (i32), `ut_tv` (2 x i32), `ut_addr_v6[4]` and padding.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn parse_utmp(content: &[u8]) -> Vec<LoginRecord> {
+    let text = |bytes: &[u8]| {
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        String::from_utf8_lossy(&bytes[..end]).into_owned()
+    };
+    content
+        .chunks_exact(UTMP_RECORD_SIZE)
+        .filter(|record| i16::from_ne_bytes([record[0], record[1]]) == USER_PROCESS)
+        .map(|record| {
+            let host = text(&record[76..332]);
+            LoginRecord {
+                pid: i32::from_ne_bytes(record[4..8].try_into().unwrap()) as u32,
+                session: i32::from_ne_bytes(record[336..340].try_into().unwrap()) as u32,
+                terminal: text(&record[8..40]),
+                user: text(&record[44..76]),
+                host: (!host.is_empty()).then_some(host),
+                login_time: i32::from_ne_bytes(record[340..344].try_into().unwrap()) as u32 as u64,
+            }
+        })
+        .filter(|record| !record.user.is_empty())
+        .collect()
+}
+
+/// Reads the user sessions tracked by systemd-logind
+///
+/// Each file in `root` is a `KEY=value` list describing one session. Greeter
+/// and background sessions are skipped, as they are not logins.
+#[cfg(target_os = "linux")]
+fn read_logind_sessions(root: &Path) -> Vec<LoginRecord> {
+    let Ok(files) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    files
+        .flatten()
+        .filter_map(|file| {
+            let content = std::fs::read_to_string(file.path()).ok()?;
+            let fields: std::collections::HashMap<&str, &str> = content
+                .lines()
+                .filter_map(|line| line.split_once('='))
+                .collect();
+            if fields.get("CLASS") != Some(&"user") || fields.get("STATE") == Some(&"closing") {
+                return None;
+            }
+            Some(LoginRecord {
+                user: fields.get("USER")?.to_string(),
+                terminal: fields
+                    .get("TTY")
+                    .or_else(|| fields.get("DISPLAY"))
+                    .map(|terminal| terminal.to_string())
+                    .unwrap_or_default(),
+                host: fields.get("REMOTE_HOST").map(|host| host.to_string()),
+                pid: fields.get("LEADER")?.parse().ok()?,
+                session: 0,
+                // REALTIME is in microseconds
+                login_time: fields
+                    .get("REALTIME")
+                    .and_then(|time| time.parse::<u64>().ok())
+                    .map_or(0, |time| time / 1_000_000),
+            })
+        })
+        .collect()
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[cfg(target_env = "gnu")]
+    fn record(kind: i16, user: &str, terminal: &str, host: &str) -> Vec<u8> {
+        let mut record = vec![0u8; UTMP_RECORD_SIZE];
+        record[0..2].copy_from_slice(&kind.to_ne_bytes());
+        record[4..8].copy_from_slice(&1234i32.to_ne_bytes());
+        record[8..8 + terminal.len()].copy_from_slice(terminal.as_bytes());
+        record[44..44 + user.len()].copy_from_slice(user.as_bytes());
+        record[76..76 + host.len()].copy_from_slice(host.as_bytes());
+        record[340..344].copy_from_slice(&1_700_000_000i32.to_ne_bytes());
+        record
+    }
+
+    /// Tests parsing of utmp records
+    #[cfg(target_env = "gnu")]
+    #[test]
+    fn test_parse_utmp() {
+        let mut content = record(2, "reboot", "~", "");
+        content.extend(record(USER_PROCESS, "alice", "pts/0", "10.0.0.5"));
+        content.extend(record(USER_PROCESS, "bob", "tty1", ""));
+
+        let records = parse_utmp(&content);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].user, "alice");
+        assert_eq!(records[0].terminal, "pts/0");
+        assert_eq!(records[0].host.as_deref(), Some("10.0.0.5"));
+        assert_eq!(records[0].pid, 1234);
+        assert_eq!(records[0].login_time, 1_700_000_000);
+        assert_eq!(records[1].host, None);
+    }
+
+    /// Tests reading logins from logind session files
+    #[test]
+    fn test_read_logind_sessions() {
+        let root = std::env::temp_dir().join(format!("neohtop-logind-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(
+            root.join("3"),
+            "UID=1000\nUSER=alice\nACTIVE=1\nSTATE=active\nREMOTE=1\nCLASS=user\n\
+             LEADER=1234\nREMOTE_HOST=10.0.0.5\nTTY=pts/0\nREALTIME=1700000000123456\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("c1"),
+            "UID=120\nUSER=gdm\nSTATE=online\nCLASS=greeter\nLEADER=900\n",
+        )
+        .unwrap();
+
+        let records = read_logind_sessions(&root);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].user, "alice");
+        assert_eq!(records[0].terminal, "pts/0");
+        assert_eq!(records[0].host.as_deref(), Some("10.0.0.5"));
+        assert_eq!(records[0].pid, 1234);
+        assert_eq!(records[0].login_time, 1_700_000_000);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    /// Tests resolving user IDs to login names
+    #[test]
+    fn test_user_name() {
+        assert_eq!(user_name("0").as_deref(), Some("root"));
+        assert_eq!(user_name("root"), None);
+    }
+
+    /// Tests attributing processes to the login that started them
+    #[test]
+    fn test_build_sessions() {
+        let process = |pid: u32, ppid: u32, session_id: u32| ProcessInfo {
+            pid,
+            ppid,
+            name: "bash".to_string(),
+            cpu_usage: 10.0,
+            memory_usage: 1024,
+            status: "Running".to_string(),
+            user: "1000".to_string(),
+            command: "bash".to_string(),
+            executable: "/usr/bin/bash".to_string(),
+            threads: None,
+            root: "/".to_string(),
+            virtual_memory: 0,
+            start_time: 1_700_000_000,
+            run_time: 60,
+            disk_usage: (0, 0),
+            session_id: Some(session_id),
+        };
+        // sshd-session (100) records the login; the shell (101) leads the session
+        let processes = [
+            process(100, 1, 90),
+            process(101, 100, 101),
+            process(102, 101, 101),
+            process(200, 1, 200),
+        ];
+        let record = LoginRecord {
+            user: "alice".to_string(),
+            terminal: "pts/0".to_string(),
+            host: Some("10.0.0.5".to_string()),
+            pid: 100,
+            session: 0,
+            login_time: 1_700_000_000,
+        };
+
+        let sessions = build_sessions(vec![record], &processes);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].process_count, 2);
+        assert_eq!(sessions[0].cpu_usage, 20.0);
+        assert_eq!(sessions[0].processes[1].pid, 102);
+    }
+}
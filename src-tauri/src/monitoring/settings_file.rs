@@ -0,0 +1,36 @@
+//! Persisted settings
+//!
+//! Settings changed from the frontend are kept as small JSON files in the
+//! app data directory so they survive a restart.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+
+/// Reads a settings file
+///
+/// # Returns
+///
+/// The stored value, or `None` if the file does not exist or cannot be parsed
+pub fn load<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Writes a settings file, replacing it atomically
+///
+/// # Errors
+///
+/// Returns an error string if the directory or file cannot be written
+pub fn save<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)
+            .map_err(|e| format!("Failed to create {}: {}", directory.display(), e))?;
+    }
+    let content = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    // Written next to the target and renamed, so a crash never leaves half a file
+    let temporary = path.with_extension("tmp");
+    std::fs::write(&temporary, content)
+        .and_then(|()| std::fs::rename(&temporary, path))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
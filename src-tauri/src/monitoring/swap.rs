@@ -0,0 +1,161 @@
+//! Swap statistics
+//!
+//! This module reports swap usage together with swap activity. Allocated swap
+//! is harmless; pages moving in and out of swap every second are what makes a
+//! machine thrash, so both are tracked.
+
+use super::SwapStats;
+#[cfg(target_os = "linux")]
+use super::{procfs, SwapDevice, ZramStats};
+use std::fmt::Debug;
+#[cfg(target_os = "linux")]
+use std::time::Instant;
+use sysinfo::SystemExt;
+
+/// Tracks swap activity counters between samples
+#[derive(Debug, Default)]
+pub struct SwapMonitor {
+    /// Time and cumulative (pages in, pages out) of the previous sample
+    #[cfg(target_os = "linux")]
+    last_counters: Option<(Instant, u64, u64)>,
+}
+
+impl SwapMonitor {
+    /// Creates a new swap monitor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collects current swap statistics
+    ///
+    /// # Arguments
+    ///
+    /// * `sys` - System information provider
+    pub fn collect_stats(&mut self, sys: &sysinfo::System) -> SwapStats {
+        SwapStats {
+            total: sys.total_swap(),
+            used: sys.used_swap(),
+            free: sys.free_swap(),
+            ..self.collect_activity()
+        }
+    }
+
+    /// Collects per-device details and page rates
+    #[cfg(target_os = "linux")]
+    fn collect_activity(&mut self) -> SwapStats {
+        let mut stats = SwapStats {
+            devices: Self::read_devices(),
+            ..Default::default()
+        };
+        if let Some((pages_in, pages_out)) = Self::read_counters() {
+            let now = Instant::now();
+            if let Some((last_time, last_in, last_out)) = self.last_counters {
+                let elapsed = now.duration_since(last_time).as_secs_f64();
+                if elapsed > 0.0 {
+                    stats.pages_in_per_sec =
+                        pages_in.checked_sub(last_in).map(|d| d as f64 / elapsed);
+                    stats.pages_out_per_sec =
+                        pages_out.checked_sub(last_out).map(|d| d as f64 / elapsed);
+                }
+            }
+            self.last_counters = Some((now, pages_in, pages_out));
+        }
+        stats
+    }
+
+    /// Collects per-device details and page rates
+    #[cfg(not(target_os = "linux"))]
+    fn collect_activity(&mut self) -> SwapStats {
+        SwapStats::default()
+    }
+
+    /// Reads cumulative swap-in and swap-out page counts from `/proc/vmstat`
+    #[cfg(target_os = "linux")]
+    fn read_counters() -> Option<(u64, u64)> {
+        let content = std::fs::read_to_string("/proc/vmstat").ok()?;
+        let values = procfs::parse_key_values(&content);
+        Some((*values.get("pswpin")?, *values.get("pswpout")?))
+    }
+
+    /// Reads the active swap areas from `/proc/swaps`
+    #[cfg(target_os = "linux")]
+    fn read_devices() -> Vec<SwapDevice> {
+        std::fs::read_to_string("/proc/swaps")
+            .map(|content| Self::parse_swaps(&content))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|mut device| {
+                device.zram = Self::read_zram(&device.name);
+                device
+            })
+            .collect()
+    }
+
+    /// Parses the contents of `/proc/swaps` (sizes are reported in KiB)
+    #[cfg(target_os = "linux")]
+    fn parse_swaps(content: &str) -> Vec<SwapDevice> {
+        content
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let [name, kind, size, used, priority] = fields.as_slice() else {
+                    return None;
+                };
+                Some(SwapDevice {
+                    // Paths with spaces are escaped as \040
+                    name: name.replace("\\040", " "),
+                    kind: kind.to_string(),
+                    size: size.parse::<u64>().ok()? * 1024,
+                    used: used.parse::<u64>().ok()? * 1024,
+                    priority: priority.parse().ok()?,
+                    zram: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Reads compression statistics for a `/dev/zramN` swap device
+    #[cfg(target_os = "linux")]
+    fn read_zram(device: &str) -> Option<ZramStats> {
+        let name = device.strip_prefix("/dev/")?;
+        if !name.starts_with("zram") {
+            return None;
+        }
+        let mm_stat = procfs::read_string(format!("/sys/block/{}/mm_stat", name))?;
+        let mut fields = mm_stat.split_whitespace().map(|f| f.parse::<u64>().ok());
+        let original_size = fields.next()??;
+        let compressed_size = fields.next()??;
+        let memory_used = fields.next()??;
+        Some(ZramStats {
+            original_size,
+            compressed_size,
+            memory_used,
+            compression_ratio: if compressed_size > 0 {
+                original_size as f64 / compressed_size as f64
+            } else {
+                0.0
+            },
+        })
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    /// Tests parsing of /proc/swaps
+    #[test]
+    fn test_parse_swaps() {
+        let content = "Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority\n\
+                       /dev/zram0                              partition\t8388604\t\t1024\t\t100\n\
+                       /swap\\040file                           file\t\t2097148\t\t0\t\t-2\n";
+        let devices = SwapMonitor::parse_swaps(content);
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].kind, "partition");
+        assert_eq!(devices[0].used, 1024 * 1024);
+        assert_eq!(devices[0].priority, 100);
+        assert_eq!(devices[1].name, "/swap file");
+        assert_eq!(devices[1].priority, -2);
+    }
+}
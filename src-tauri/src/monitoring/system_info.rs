@@ -0,0 +1,272 @@
+//! Host and operating system information
+//!
+//! This module describes the machine the monitor runs on: kernel, distro,
+//! hostname, boot time and architecture, whether it is a virtual machine or
+//! a container, which init system it runs, and how many processes, threads
+//! and logged in users it has.
+
+use super::sessions;
+use super::SystemInfo;
+use std::collections::HashSet;
+#[cfg(target_os = "linux")]
+use std::path::Path;
+use sysinfo::SystemExt;
+
+/// Reads information about the host
+///
+/// # Arguments
+///
+/// * `sys` - System information provider
+pub fn read_system_info(sys: &sysinfo::System) -> SystemInfo {
+    let os_release = read_os_release();
+    let users: HashSet<String> = sessions::read_login_records()
+        .into_iter()
+        .map(|record| record.user)
+        .collect();
+
+    SystemInfo {
+        kernel_version: sys.kernel_version(),
+        os_name: os_release
+            .as_ref()
+            .and_then(|fields| fields.get("PRETTY_NAME").cloned())
+            .or_else(|| sys.long_os_version()),
+        os_id: os_release
+            .as_ref()
+            .and_then(|fields| fields.get("ID").cloned()),
+        os_version: os_release
+            .as_ref()
+            .and_then(|fields| fields.get("VERSION_ID").cloned())
+            .or_else(|| sys.os_version()),
+        hostname: sys.host_name(),
+        boot_time: sys.boot_time(),
+        architecture: std::env::consts::ARCH.to_string(),
+        virtualization: detect_virtualization(),
+        container: detect_container(),
+        init_system: detect_init_system(),
+        process_count: read_process_count(sys),
+        thread_count: read_thread_count(),
+        logged_in_users: users.len(),
+    }
+}
+
+/// Reads the `KEY=value` pairs of os-release
+#[cfg(target_os = "linux")]
+fn read_os_release() -> Option<std::collections::HashMap<String, String>> {
+    let content = std::fs::read_to_string("/etc/os-release")
+        .or_else(|_| std::fs::read_to_string("/usr/lib/os-release"))
+        .ok()?;
+    Some(parse_os_release(&content))
+}
+
+/// Reads the `KEY=value` pairs of os-release
+#[cfg(not(target_os = "linux"))]
+fn read_os_release() -> Option<std::collections::HashMap<String, String>> {
+    None
+}
+
+/// Parses os-release, removing shell quoting from the values
+#[cfg(target_os = "linux")]
+fn parse_os_release(content: &str) -> std::collections::HashMap<String, String> {
+    content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value);
+            Some((key.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Detects the hypervisor, in the spirit of `systemd-detect-virt --vm`
+#[cfg(target_os = "linux")]
+fn detect_virtualization() -> Option<String> {
+    let dmi = |file: &str| {
+        super::procfs::read_string(Path::new("/sys/class/dmi/id").join(file)).unwrap_or_default()
+    };
+    let identity = format!(
+        "{} {} {}",
+        dmi("sys_vendor"),
+        dmi("product_name"),
+        dmi("bios_vendor")
+    );
+    let known = [
+        ("KVM", "kvm"),
+        ("QEMU", "qemu"),
+        ("VMware", "vmware"),
+        ("VirtualBox", "oracle"),
+        ("innotek", "oracle"),
+        ("Xen", "xen"),
+        ("Amazon EC2", "amazon"),
+        ("Google Compute Engine", "google"),
+        ("Parallels", "parallels"),
+        ("Bochs", "bochs"),
+        ("BHYVE", "bhyve"),
+        ("Virtual Machine", "microsoft"),
+    ];
+    if let Some((_, name)) = known.iter().find(|(marker, _)| identity.contains(marker)) {
+        return Some(name.to_string());
+    }
+    if let Some(kind) = super::procfs::read_string("/sys/hypervisor/type") {
+        return Some(kind);
+    }
+    // The CPUID hypervisor bit is set by every hypervisor, known or not
+    let hypervisor_flag = std::fs::read_to_string("/proc/cpuinfo")
+        .map(|content| {
+            content
+                .lines()
+                .any(|line| line.starts_with("flags") && line.contains(" hypervisor"))
+        })
+        .unwrap_or(false);
+    hypervisor_flag.then(|| "unknown".to_string())
+}
+
+/// Detects the hypervisor
+#[cfg(not(target_os = "linux"))]
+fn detect_virtualization() -> Option<String> {
+    None
+}
+
+/// Detects the container runtime, in the spirit of `systemd-detect-virt --container`
+#[cfg(target_os = "linux")]
+fn detect_container() -> Option<String> {
+    if Path::new("/.dockerenv").exists() {
+        return Some("docker".to_string());
+    }
+    if Path::new("/run/.containerenv").exists() {
+        return Some("podman".to_string());
+    }
+    // Set by systemd-nspawn, LXC and most other managers; readable as root only
+    if let Ok(environ) = std::fs::read("/proc/1/environ") {
+        let container = environ
+            .split(|&b| b == 0)
+            .find_map(|entry| entry.strip_prefix(b"container="));
+        if let Some(name) = container {
+            return Some(String::from_utf8_lossy(name).into_owned());
+        }
+    }
+    if let Some(osrelease) = super::procfs::read_string("/proc/sys/kernel/osrelease") {
+        if osrelease.to_lowercase().contains("microsoft") {
+            return Some("wsl".to_string());
+        }
+    }
+    if Path::new("/proc/vz").exists() && !Path::new("/proc/bc").exists() {
+        return Some("openvz".to_string());
+    }
+    let cgroup = std::fs::read_to_string("/proc/1/cgroup").unwrap_or_default();
+    ["kubepods", "docker", "lxc"]
+        .iter()
+        .find(|marker| cgroup.contains(*marker))
+        .map(|marker| marker.to_string())
+}
+
+/// Detects the container runtime
+#[cfg(not(target_os = "linux"))]
+fn detect_container() -> Option<String> {
+    None
+}
+
+/// Identifies the init system running as PID 1
+#[cfg(target_os = "linux")]
+fn detect_init_system() -> Option<String> {
+    if Path::new("/run/systemd/system").exists() {
+        return Some("systemd".to_string());
+    }
+    let comm = super::procfs::read_string("/proc/1/comm")?;
+    let name = match comm.as_str() {
+        "openrc-init" => "openrc",
+        "runit" | "runit-init" => "runit",
+        "s6-svscan" => "s6",
+        "init" if Path::new("/sbin/openrc").exists() => "openrc",
+        "init" => "sysvinit",
+        other => other,
+    };
+    Some(name.to_string())
+}
+
+/// Identifies the init system
+#[cfg(target_os = "macos")]
+fn detect_init_system() -> Option<String> {
+    Some("launchd".to_string())
+}
+
+/// Identifies the init system
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn detect_init_system() -> Option<String> {
+    None
+}
+
+/// Counts the processes on the system from the PID directories in `/proc`
+///
+/// Refreshing the process list of the shared `System` here would restart
+/// the interval sysinfo measures per-process CPU usage over.
+#[cfg(target_os = "linux")]
+fn read_process_count(sys: &sysinfo::System) -> usize {
+    match std::fs::read_dir("/proc") {
+        Ok(entries) => entries
+            .flatten()
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| name.bytes().all(|b| b.is_ascii_digit()))
+            })
+            .count(),
+        Err(_) => sys.processes().len(),
+    }
+}
+
+/// Counts the processes seen by the last process list refresh
+#[cfg(not(target_os = "linux"))]
+fn read_process_count(sys: &sysinfo::System) -> usize {
+    sys.processes().len()
+}
+
+/// Reads the number of threads on the system from `/proc/loadavg`
+#[cfg(target_os = "linux")]
+fn read_thread_count() -> Option<usize> {
+    // The fourth field is "runnable/total" scheduling entities
+    let loadavg = super::procfs::read_string("/proc/loadavg")?;
+    let (_, total) = loadavg.split_whitespace().nth(3)?.split_once('/')?;
+    total.parse().ok()
+}
+
+/// Reads the number of threads on the system
+#[cfg(not(target_os = "linux"))]
+fn read_thread_count() -> Option<usize> {
+    None
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    /// Tests parsing of os-release
+    #[test]
+    fn test_parse_os_release() {
+        let fields = parse_os_release(
+            "# comment\n\
+             PRETTY_NAME=\"Debian GNU/Linux 12 (bookworm)\"\n\
+             ID=debian\n\
+             VERSION_ID='12'\n",
+        );
+        assert_eq!(fields["PRETTY_NAME"], "Debian GNU/Linux 12 (bookworm)");
+        assert_eq!(fields["ID"], "debian");
+        assert_eq!(fields["VERSION_ID"], "12");
+    }
+
+    /// Tests reading information about the running host
+    #[test]
+    fn test_read_system_info() {
+        let sys = sysinfo::System::new();
+        let info = read_system_info(&sys);
+        assert!(!info.architecture.is_empty());
+        assert!(info.process_count > 0);
+        assert!(info.thread_count.unwrap() >= info.process_count);
+    }
+}
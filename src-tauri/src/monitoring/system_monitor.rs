@@ -3,70 +3,139 @@
 //! This module handles collection and monitoring of system-wide statistics
 //! including CPU, memory, network, and disk usage.
 
-use super::SystemStats;
+use super::{
+    cpu_info, cpu_times::CpuTimesMonitor, disk_io::DiskIoMonitor, history::MetricsHistory, memory,
+    mounts::MountMonitor, network::NetworkMonitor, numa, power, pressure, swap::SwapMonitor,
+    MetricsRange, MountFilter, NetworkFilter, SystemStats,
+};
 use std::fmt::Debug;
 use std::path::Path;
-use std::time::Instant;
-use sysinfo::{CpuExt, Disk, DiskExt, NetworkExt, NetworksExt, SystemExt};
+use sysinfo::{CpuExt, Disk, DiskExt, SystemExt};
 
 /// Monitors system-wide statistics
 #[derive(Debug)]
 pub struct SystemMonitor {
-    /// Tracks network usage between updates
-    last_network_update: (Instant, u64, u64),
+    /// Collects per-interface network statistics
+    network_monitor: NetworkMonitor,
+    /// Tracks swap activity between updates
+    swap_monitor: SwapMonitor,
+    /// Collects per-mount filesystem statistics
+    mount_monitor: MountMonitor,
+    /// Tracks block device I/O between updates
+    disk_io_monitor: DiskIoMonitor,
+    /// Tracks the CPU time breakdown between updates
+    cpu_times_monitor: CpuTimesMonitor,
+    /// Downsampled history of the collected statistics
+    history: MetricsHistory,
+}
+
+impl Default for SystemMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SystemMonitor {
     /// Creates a new system monitor instance
     ///
-    /// # Arguments
-    ///
-    /// * `sys` - System information provider for initial readings
-    pub fn new(sys: &sysinfo::System) -> Self {
-        let initial_rx: u64 = sys
-            .networks()
-            .iter()
-            .map(|(_, data)| data.total_received())
-            .sum();
-        let initial_tx: u64 = sys
-            .networks()
-            .iter()
-            .map(|(_, data)| data.total_transmitted())
-            .sum();
-
+    /// Network baselines are taken on the first collection, once the
+    /// interface list has been refreshed.
+    pub fn new() -> Self {
         Self {
-            last_network_update: (Instant::now(), initial_rx, initial_tx),
+            network_monitor: NetworkMonitor::new(),
+            swap_monitor: SwapMonitor::new(),
+            mount_monitor: MountMonitor::new(),
+            disk_io_monitor: DiskIoMonitor::new(),
+            cpu_times_monitor: CpuTimesMonitor::new(),
+            history: MetricsHistory::new(),
         }
     }
 
-    /// Collects current system statistics
+    /// Returns the network interface filter
+    pub fn network_filter(&self) -> &NetworkFilter {
+        self.network_monitor.filter()
+    }
+
+    /// Replaces the network interface filter
+    pub fn set_network_filter(&mut self, filter: NetworkFilter) {
+        self.network_monitor.set_filter(filter);
+    }
+
+    /// Returns the mounted filesystem filter
+    pub fn mount_filter(&self) -> &MountFilter {
+        self.mount_monitor.filter()
+    }
+
+    /// Replaces the mounted filesystem filter
+    pub fn set_mount_filter(&mut self, filter: MountFilter) {
+        self.mount_monitor.set_filter(filter);
+    }
+
+    /// Returns the recorded metrics between two points in time
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - Start of the range in milliseconds since the Unix epoch
+    /// * `end` - End of the range in milliseconds since the Unix epoch
+    pub fn history(&self, start: u64, end: u64) -> MetricsRange {
+        self.history.range(start, end)
+    }
+
+    /// Collects current system statistics and records them in the history
     ///
     /// # Arguments
     ///
     /// * `sys` - System information provider
     pub fn collect_stats(&mut self, sys: &sysinfo::System) -> SystemStats {
-        let (network_rx, network_tx) = self.calculate_network_stats(sys);
+        let (network_interfaces, (network_rx, network_tx)) =
+            self.network_monitor.collect_interfaces(sys);
+        // Interfaces without a valid delta this sample contribute nothing
+        let filtered_network_rx = network_interfaces
+            .iter()
+            .filter_map(|i| i.rx_bytes_per_sec)
+            .sum();
+        let filtered_network_tx = network_interfaces
+            .iter()
+            .filter_map(|i| i.tx_bytes_per_sec)
+            .sum();
         let (disk_total, disk_used, disk_free) = self.calculate_disk_stats(sys);
+        let memory_breakdown = memory::read_memory_breakdown();
 
-        SystemStats {
+        let stats = SystemStats {
             cpu_usage: sys.cpus().iter().map(|cpu| cpu.cpu_usage()).collect(),
+            cpu_times: self.cpu_times_monitor.collect_stats(),
+            cpu_frequencies: cpu_info::read_frequencies(sys),
             memory_total: sys.total_memory(),
             memory_used: sys.used_memory(),
             memory_free: sys.total_memory() - sys.used_memory(),
-            memory_cached: sys.total_memory()
-                - (sys.used_memory() + (sys.total_memory() - sys.used_memory())),
+            memory_cached: memory_breakdown
+                .as_ref()
+                .map(|breakdown| breakdown.reclaimable_cache())
+                .unwrap_or_else(|| sys.available_memory().saturating_sub(sys.free_memory())),
+            memory_breakdown,
+            numa_nodes: numa::read_nodes(),
+            swap: self.swap_monitor.collect_stats(sys),
             uptime: sys.uptime(),
             load_avg: [
                 sys.load_average().one,
                 sys.load_average().five,
                 sys.load_average().fifteen,
             ],
+            pressure: pressure::read_system_pressure(),
             network_rx_bytes: network_rx,
             network_tx_bytes: network_tx,
+            filtered_network_rx_bytes: filtered_network_rx,
+            filtered_network_tx_bytes: filtered_network_tx,
+            network_interfaces,
             disk_total_bytes: disk_total,
             disk_used_bytes: disk_used,
             disk_free_bytes: disk_free,
-        }
+            mounts: self.mount_monitor.collect_mounts(sys),
+            block_devices: self.disk_io_monitor.collect_devices(),
+            power: power::read_power_stats(),
+        };
+        self.history.record(&stats);
+        stats
     }
 
     /// Filters disks based on platform-specific criteria
@@ -84,27 +153,6 @@ impl SystemMonitor {
         disks.iter().collect()
     }
 
-    /// Calculates network usage rates
-    fn calculate_network_stats(&mut self, sys: &sysinfo::System) -> (u64, u64) {
-        let current_rx: u64 = sys
-            .networks()
-            .iter()
-            .map(|(_, data)| data.total_received())
-            .sum();
-        let current_tx: u64 = sys
-            .networks()
-            .iter()
-            .map(|(_, data)| data.total_transmitted())
-            .sum();
-
-        let elapsed = self.last_network_update.0.elapsed().as_secs_f64();
-        let rx_rate = ((current_rx - self.last_network_update.1) as f64 / elapsed) as u64;
-        let tx_rate = ((current_tx - self.last_network_update.2) as f64 / elapsed) as u64;
-
-        self.last_network_update = (Instant::now(), current_rx, current_tx);
-        (rx_rate, tx_rate)
-    }
-
     /// Calculates disk usage statistics
     fn calculate_disk_stats(&self, sys: &sysinfo::System) -> (u64, u64, u64) {
         let disks = Self::filter_disks(sys.disks());
@@ -126,21 +174,22 @@ mod tests {
     /// Tests creation of system monitor
     #[test]
     fn test_system_monitor_creation() {
-        let sys = System::new();
-        let monitor = SystemMonitor::new(&sys);
-        assert!(monitor.last_network_update.1 >= 0);
-        assert!(monitor.last_network_update.2 >= 0);
+        let monitor = SystemMonitor::new();
+        assert_eq!(monitor.network_filter().exclude, vec!["lo", "lo0"]);
     }
 
     /// Tests system statistics collection
     #[test]
     fn test_stats_collection() {
         let mut sys = System::new();
-        let mut monitor = SystemMonitor::new(&sys);
+        let mut monitor = SystemMonitor::new();
         sys.refresh_all();
+        sys.refresh_networks_list();
 
         let stats = monitor.collect_stats(&sys);
         assert!(!stats.cpu_usage.is_empty());
         assert!(stats.memory_total > 0);
+        assert_eq!(stats.network_rx_bytes, 0);
+        assert_eq!(stats.filtered_network_rx_bytes, 0);
     }
 }
@@ -1,7 +1,46 @@
-use serde::Serialize;
+use super::process_history::ProcessSeries;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use sysinfo::{DiskUsage, ProcessStatus};
 
+/// Stable identity of a process
+///
+/// A bare PID is reused by the kernel once the process exits, so every cache
+/// and every action is keyed on the PID together with the process start time.
+/// Start times have one-second resolution, which is far shorter than the time
+/// it takes the kernel to cycle through the PID space.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ProcessKey {
+    /// Process ID
+    pub pid: u32,
+    /// Process start time (Unix timestamp)
+    pub start_time: u64,
+}
+
+/// Signals that can be sent to a process from the frontend
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ProcessSignal {
+    /// Hangup (SIGHUP)
+    Hup,
+    /// Interrupt (SIGINT)
+    Int,
+    /// Quit (SIGQUIT)
+    Quit,
+    /// Forced termination (SIGKILL)
+    Kill,
+    /// Graceful termination request (SIGTERM)
+    Term,
+    /// Pause execution (SIGSTOP)
+    Stop,
+    /// Resume a stopped process (SIGCONT)
+    Cont,
+    /// User-defined signal 1 (SIGUSR1)
+    Usr1,
+    /// User-defined signal 2 (SIGUSR2)
+    Usr2,
+}
+
 /// Internal representation of process data collected from the system
 /// This struct is used internally and not exposed directly to the frontend
 #[derive(Clone, Debug)]
@@ -12,6 +51,8 @@ pub(crate) struct ProcessData {
     pub name: String,
     /// Complete command line arguments
     pub cmd: Vec<String>,
+    /// Path of the executable, empty if unknown
+    pub exe: String,
     /// User ID that owns the process
     pub user_id: Option<String>,
     /// CPU usage as percentage (0-100)
@@ -22,8 +63,6 @@ pub(crate) struct ProcessData {
     pub status: ProcessStatus,
     /// Parent process ID
     pub ppid: Option<u32>,
-    /// Environment variables
-    pub environ: Vec<String>,
     /// Root directory of the process
     pub root: String,
     /// Virtual memory usage in bytes
@@ -38,6 +77,36 @@ pub(crate) struct ProcessData {
     pub session_id: Option<u32>,
 }
 
+impl ProcessData {
+    /// Returns the stable identity of this process
+    pub fn key(&self) -> ProcessKey {
+        ProcessKey {
+            pid: self.pid,
+            start_time: self.start_time,
+        }
+    }
+}
+
+/// Running statistics tracked for a live process between refreshes
+/// Used to describe the process once it has exited
+#[derive(Clone, Debug)]
+pub(crate) struct ProcessTracker {
+    /// Parent process ID
+    pub ppid: u32,
+    /// Most recent CPU usage as percentage (0-100)
+    pub cpu_usage: f32,
+    /// Most recent physical memory usage in bytes
+    pub memory: u64,
+    /// Highest CPU usage observed
+    pub peak_cpu_usage: f32,
+    /// Highest physical memory usage observed in bytes
+    pub peak_memory: u64,
+    /// Total disk I/O over the process lifetime (read bytes, written bytes)
+    pub total_disk_usage: (u64, u64),
+    /// Downsampled CPU, memory and I/O history
+    pub history: ProcessSeries,
+}
+
 /// Static information about a process that doesn't change frequently
 /// Used for caching purposes to avoid frequent updates of stable data
 #[derive(Clone, Debug)]
@@ -46,13 +115,15 @@ pub struct ProcessStaticInfo {
     pub name: String,
     /// Full command string
     pub command: String,
+    /// Path of the executable
+    pub executable: String,
     /// Username of the process owner
     pub user: String,
 }
 
 /// Process information exposed to the frontend via Tauri
 /// Contains formatted and filtered process data for UI consumption
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ProcessInfo {
     /// Process ID
     pub pid: u32,
@@ -70,10 +141,10 @@ pub struct ProcessInfo {
     pub user: String,
     /// Full command string
     pub command: String,
+    /// Path of the executable, empty if it cannot be read
+    pub executable: String,
     /// Number of threads (if available)
     pub threads: Option<u32>,
-    /// Environment variables
-    pub environ: Vec<String>,
     /// Root directory of the process
     pub root: String,
     /// Virtual memory usage in bytes
@@ -88,32 +159,1061 @@ pub struct ProcessInfo {
     pub session_id: Option<u32>,
 }
 
+impl ProcessInfo {
+    /// Returns the stable identity of this process
+    pub fn key(&self) -> ProcessKey {
+        ProcessKey {
+            pid: self.pid,
+            start_time: self.start_time,
+        }
+    }
+}
+
+/// A process that has recently disappeared from the process list
+/// Keeps its last known statistics so short spikes and crashes can be inspected
+#[derive(Serialize, Clone, Debug)]
+pub struct ExitedProcess {
+    /// Process ID
+    pub pid: u32,
+    /// Parent process ID
+    pub ppid: u32,
+    /// Process name
+    pub name: String,
+    /// Full command string
+    pub command: String,
+    /// Username of the process owner
+    pub user: String,
+    /// Process start time (Unix timestamp)
+    pub start_time: u64,
+    /// Time the process exited, or was first noticed missing (Unix timestamp)
+    pub exit_time: u64,
+    /// Total lifetime in seconds
+    pub lifetime: u64,
+    /// Last observed CPU usage as percentage (0-100)
+    pub cpu_usage: f32,
+    /// Last observed physical memory usage in bytes
+    pub memory_usage: u64,
+    /// Highest CPU usage observed over the process lifetime
+    pub peak_cpu_usage: f32,
+    /// Highest physical memory usage observed in bytes
+    pub peak_memory_usage: u64,
+    /// Total disk I/O over the process lifetime (read bytes, written bytes)
+    pub disk_usage: (u64, u64),
+    /// Exit code, if the process terminated normally and it could be observed
+    pub exit_code: Option<i32>,
+    /// Terminating signal, if the process was killed and it could be observed
+    pub exit_signal: Option<i32>,
+}
+
+/// A single environment variable of a process
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct EnvironmentVariable {
+    /// Variable name
+    pub key: String,
+    /// Variable value, replaced by a placeholder if `masked` is set
+    pub value: String,
+    /// Whether the value was hidden because the name looks like a secret
+    pub masked: bool,
+}
+
+/// Environment of a single process, fetched on demand
+#[derive(Serialize, Debug)]
+pub struct ProcessEnvironment {
+    /// Identity of the process
+    pub key: ProcessKey,
+    /// Variables sorted by name
+    pub variables: Vec<EnvironmentVariable>,
+}
+
+/// A variable present in both environments with different values
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct EnvironmentChange {
+    /// Variable name
+    pub key: String,
+    /// Value in the left process (possibly masked)
+    pub left: String,
+    /// Value in the right process (possibly masked)
+    pub right: String,
+    /// Whether the values were hidden because the name looks like a secret
+    pub masked: bool,
+}
+
+/// Difference between the environments of two processes
+#[derive(Serialize, Debug)]
+pub struct EnvironmentDiff {
+    /// Identity of the left process
+    pub left: ProcessKey,
+    /// Identity of the right process
+    pub right: ProcessKey,
+    /// Variables only set in the left process
+    pub only_left: Vec<EnvironmentVariable>,
+    /// Variables only set in the right process
+    pub only_right: Vec<EnvironmentVariable>,
+    /// Variables set in both processes with different values
+    pub changed: Vec<EnvironmentChange>,
+    /// Number of variables identical in both processes
+    pub unchanged: usize,
+}
+
+/// One point of a process's metric history, averaged over the bucket
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ProcessSample {
+    /// Start of the bucket (Unix timestamp)
+    pub timestamp: u64,
+    /// CPU usage as percentage (0-100)
+    pub cpu_usage: f32,
+    /// Physical memory usage in bytes
+    pub memory_usage: u64,
+    /// Bytes read from disk per second
+    pub disk_read_bytes_per_sec: u64,
+    /// Bytes written to disk per second
+    pub disk_written_bytes_per_sec: u64,
+}
+
+/// Recent metric history of a single process
+#[derive(Serialize, Clone, Debug)]
+pub struct ProcessHistory {
+    /// Identity of the process
+    pub key: ProcessKey,
+    /// Time covered by each sample in seconds
+    pub resolution: u64,
+    /// Time the process exited (Unix timestamp), absent while it is running
+    pub exit_time: Option<u64>,
+    /// Samples ordered by time, covering up to the last hour
+    pub samples: Vec<ProcessSample>,
+}
+
+/// A user logged in through utmp, with the processes of their session
+#[derive(Serialize, Clone, Debug)]
+pub struct LoginSession {
+    /// Login name
+    pub user: String,
+    /// Terminal, e.g. "pts/0" or "tty1"
+    pub terminal: String,
+    /// Remote host for network logins
+    pub host: Option<String>,
+    /// Login time (Unix timestamp)
+    pub login_time: u64,
+    /// PID of the login process
+    pub pid: u32,
+    /// Number of processes in the session
+    pub process_count: usize,
+    /// Summed CPU usage of the session's processes
+    pub cpu_usage: f32,
+    /// Summed physical memory usage of the session's processes in bytes
+    pub memory_usage: u64,
+    /// The processes in the session
+    pub processes: Vec<ProcessKey>,
+}
+
+/// Property by which processes are grouped
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessGrouping {
+    /// Owner of the process
+    User,
+    /// Process name, e.g. all "postgres" processes
+    Name,
+    /// Path of the executable, which tells apart programs sharing a name
+    Executable,
+}
+
+/// Processes sharing the same grouping key, with their summed resource usage
+#[derive(Serialize, Clone, Debug)]
+pub struct ProcessGroup {
+    /// Value the members have in common, e.g. the user or process name
+    pub key: String,
+    /// Number of processes in the group
+    pub process_count: usize,
+    /// Summed CPU usage as percentage
+    pub cpu_usage: f32,
+    /// Summed physical memory usage (RSS) in bytes
+    pub memory_usage: u64,
+    /// Summed bytes read from disk since the last refresh
+    pub disk_read_bytes: u64,
+    /// Summed bytes written to disk since the last refresh
+    pub disk_written_bytes: u64,
+    /// The processes in the group, busiest first
+    pub members: Vec<ProcessKey>,
+}
+
+/// The members of one process group with their proportional memory usage
+#[derive(Serialize, Clone, Debug)]
+pub struct ProcessGroupDetails {
+    /// The processes in the group, busiest first
+    pub members: Vec<ProcessInfo>,
+    /// Summed proportional set size of the readable members in bytes. PSS
+    /// divides each shared page among every process on the system that maps
+    /// it, so this is the group's share of memory, not its distinct pages.
+    pub pss: u64,
+    /// Number of members whose memory map could not be read
+    pub pss_skipped: usize,
+}
+
+/// Detailed breakdown of physical memory usage (Linux only)
+/// All values are in bytes unless noted otherwise
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct MemoryBreakdown {
+    /// Memory available for new allocations without swapping
+    pub available: u64,
+    /// Block device buffers
+    pub buffers: u64,
+    /// Page cache, including shared memory
+    pub cached: u64,
+    /// Shared memory and tmpfs contents (part of `cached`)
+    pub shared: u64,
+    /// Kernel slab memory that can be reclaimed
+    pub slab_reclaimable: u64,
+    /// Kernel slab memory that cannot be reclaimed
+    pub slab_unreclaimable: u64,
+    /// Memory waiting to be written back to disk
+    pub dirty: u64,
+    /// Memory actively being written back to disk
+    pub writeback: u64,
+    /// Anonymous memory mapped into user space
+    pub anon: u64,
+    /// Files mapped into memory, such as libraries
+    pub mapped: u64,
+    /// Total number of preallocated huge pages
+    pub hugepages_total: u64,
+    /// Number of unused huge pages
+    pub hugepages_free: u64,
+    /// Size of a single huge page
+    pub hugepage_size: u64,
+    /// Preallocated huge page pools, one per supported page size
+    pub hugepage_pools: Vec<HugePagePool>,
+    /// Anonymous memory backed by transparent huge pages
+    pub anon_hugepages: u64,
+    /// Shared memory and tmpfs backed by transparent huge pages
+    pub shmem_hugepages: u64,
+    /// Page cache backed by transparent huge pages
+    pub file_hugepages: u64,
+    /// Transparent huge page settings and counters, if the kernel supports them
+    pub transparent_hugepages: Option<TransparentHugePages>,
+    /// Memory currently promised to processes
+    pub committed_as: u64,
+    /// Maximum memory that can be committed under the overcommit policy
+    pub commit_limit: u64,
+}
+
+/// A pool of preallocated huge pages of one size
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HugePagePool {
+    /// Size of a page in this pool in bytes
+    pub page_size: u64,
+    /// Number of pages in the pool
+    pub total: u64,
+    /// Number of pages not yet allocated
+    pub free: u64,
+    /// Number of pages promised to mappings but not yet faulted in
+    pub reserved: u64,
+    /// Number of pages allocated above `total` through overcommit
+    pub surplus: u64,
+}
+
+/// Transparent huge page configuration and activity
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TransparentHugePages {
+    /// Active `enabled` mode: "always", "madvise" or "never"
+    pub enabled: String,
+    /// Active `defrag` mode, e.g. "madvise" or "defer"
+    pub defrag: String,
+    /// Page faults served with a huge page since boot
+    pub fault_alloc: u64,
+    /// Page faults that fell back to regular pages since boot
+    pub fault_fallback: u64,
+    /// Huge pages assembled by khugepaged since boot
+    pub collapse_alloc: u64,
+    /// Huge pages split into regular pages since boot
+    pub split_page: u64,
+}
+
+/// zram compression statistics for a swap device
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ZramStats {
+    /// Uncompressed size of the stored data in bytes
+    pub original_size: u64,
+    /// Compressed size of the stored data in bytes
+    pub compressed_size: u64,
+    /// Total memory used by the device, including allocator overhead, in bytes
+    pub memory_used: u64,
+    /// Ratio of original to compressed size
+    pub compression_ratio: f64,
+}
+
+/// A single active swap area
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SwapDevice {
+    /// Path of the swap partition or file
+    pub name: String,
+    /// Swap area type ("partition" or "file")
+    pub kind: String,
+    /// Size in bytes
+    pub size: u64,
+    /// Used space in bytes
+    pub used: u64,
+    /// Swap priority; higher priority areas are used first
+    pub priority: i32,
+    /// Compression statistics if the device is a zram device
+    pub zram: Option<ZramStats>,
+}
+
+/// Swap usage and activity
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SwapStats {
+    /// Total swap space in bytes
+    pub total: u64,
+    /// Used swap space in bytes
+    pub used: u64,
+    /// Free swap space in bytes
+    pub free: u64,
+    /// Active swap areas (Linux only)
+    pub devices: Vec<SwapDevice>,
+    /// Pages swapped in per second since the previous sample
+    pub pages_in_per_sec: Option<f64>,
+    /// Pages swapped out per second since the previous sample
+    pub pages_out_per_sec: Option<f64>,
+}
+
+/// Statistics for a single network interface
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NetworkInterface {
+    /// Interface name
+    pub name: String,
+    /// Hardware (MAC) address
+    pub mac_address: String,
+    /// Assigned IPv4 and IPv6 addresses
+    pub ip_addresses: Vec<String>,
+    /// Operational state such as "up", "down" or "unknown"
+    pub link_state: String,
+    /// Maximum transmission unit in bytes
+    pub mtu: Option<u32>,
+    /// Negotiated link speed in Mbit/s
+    pub speed_mbps: Option<u32>,
+    /// Whether the interface is virtual (bridge, veth, tunnel, loopback...)
+    pub is_virtual: bool,
+    /// Bytes received per second, absent on the first sample or after a counter reset
+    pub rx_bytes_per_sec: Option<u64>,
+    /// Bytes transmitted per second
+    pub tx_bytes_per_sec: Option<u64>,
+    /// Packets received per second
+    pub rx_packets_per_sec: Option<u64>,
+    /// Packets transmitted per second
+    pub tx_packets_per_sec: Option<u64>,
+    /// Total bytes received
+    pub rx_bytes_total: u64,
+    /// Total bytes transmitted
+    pub tx_bytes_total: u64,
+    /// Total receive errors
+    pub rx_errors: u64,
+    /// Total transmit errors
+    pub tx_errors: u64,
+    /// Total received packets dropped, where reported
+    pub rx_dropped: Option<u64>,
+    /// Total transmitted packets dropped, where reported
+    pub tx_dropped: Option<u64>,
+}
+
+/// User configurable selection of network interfaces
+/// Patterns are globs where `*` matches any run of characters
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NetworkFilter {
+    /// If not empty, only interfaces matching one of these patterns are shown
+    pub include: Vec<String>,
+    /// Interfaces matching one of these patterns are hidden
+    pub exclude: Vec<String>,
+    /// Hide all virtual interfaces (bridges, veth pairs, tunnels...)
+    pub hide_virtual: bool,
+}
+
+impl Default for NetworkFilter {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: vec!["lo".to_string(), "lo0".to_string()],
+            hide_virtual: false,
+        }
+    }
+}
+
+/// Capacity and inode usage of a mounted filesystem
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MountInfo {
+    /// Source device, e.g. "/dev/nvme0n1p2"
+    pub device: String,
+    /// Path the filesystem is mounted on
+    pub mount_point: String,
+    /// Filesystem type, e.g. "ext4"
+    pub fs_type: String,
+    /// Whether the filesystem is mounted read-only
+    pub read_only: bool,
+    /// Total size in bytes
+    pub total_bytes: u64,
+    /// Used space in bytes
+    pub used_bytes: u64,
+    /// Space available to unprivileged users in bytes
+    pub free_bytes: u64,
+    /// Total inodes, absent for filesystems without a fixed inode table
+    pub inodes_total: Option<u64>,
+    /// Used inodes
+    pub inodes_used: Option<u64>,
+    /// Free inodes
+    pub inodes_free: Option<u64>,
+}
+
+/// User configurable selection of mounted filesystems
+/// Patterns are globs where `*` matches any run of characters
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MountFilter {
+    /// Filesystem types to hide, e.g. "tmpfs" or "fuse.*"
+    pub exclude_fs_types: Vec<String>,
+    /// Mount points to hide, e.g. "/snap/*"
+    pub exclude_mount_points: Vec<String>,
+}
+
+impl Default for MountFilter {
+    fn default() -> Self {
+        Self {
+            exclude_fs_types: [
+                "autofs",
+                "binfmt_misc",
+                "bpf",
+                "cgroup",
+                "cgroup2",
+                "configfs",
+                "debugfs",
+                "devpts",
+                "devtmpfs",
+                "efivarfs",
+                "fusectl",
+                "fuse.gvfsd-fuse",
+                "fuse.portal",
+                "hugetlbfs",
+                "mqueue",
+                "nsfs",
+                "overlay",
+                "proc",
+                "pstore",
+                "ramfs",
+                "rpc_pipefs",
+                "securityfs",
+                "selinuxfs",
+                "squashfs",
+                "sysfs",
+                "tmpfs",
+                "tracefs",
+            ]
+            .iter()
+            .map(|t| t.to_string())
+            .collect(),
+            exclude_mount_points: Vec::new(),
+        }
+    }
+}
+
+/// I/O activity of a block device or partition
+/// Rates are absent until two samples of the device have been taken
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct BlockDeviceStats {
+    /// Kernel device name, e.g. "nvme0n1"
+    pub name: String,
+    /// Whether the device is virtual (device mapper, md RAID, loop, zram...),
+    /// so its I/O is also counted on the devices beneath it, if any
+    #[serde(default)]
+    pub is_virtual: bool,
+    /// Bytes read per second
+    pub read_bytes_per_sec: Option<f64>,
+    /// Bytes written per second
+    pub write_bytes_per_sec: Option<f64>,
+    /// Completed reads per second
+    pub reads_per_sec: Option<f64>,
+    /// Completed writes per second
+    pub writes_per_sec: Option<f64>,
+    /// Average time per completed read in milliseconds
+    pub read_latency_ms: Option<f64>,
+    /// Average time per completed write in milliseconds
+    pub write_latency_ms: Option<f64>,
+    /// Average number of requests queued or in service
+    pub queue_depth: Option<f64>,
+    /// Percentage of time the device was busy
+    pub utilization_percent: Option<f64>,
+    /// Requests currently in flight
+    pub in_flight: u64,
+    /// Partitions of this device
+    pub partitions: Vec<BlockDeviceStats>,
+}
+
+/// Share of CPU time spent in each kernel accounting category, in percent
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CpuTimeBreakdown {
+    /// Normal user mode
+    pub user: f64,
+    /// Niced user mode
+    pub nice: f64,
+    /// Kernel mode
+    pub system: f64,
+    /// Idle
+    pub idle: f64,
+    /// Idle while waiting for I/O to complete
+    pub iowait: f64,
+    /// Servicing hardware interrupts
+    pub irq: f64,
+    /// Servicing softirqs
+    pub softirq: f64,
+    /// Stolen by the hypervisor for other virtual machines
+    pub steal: f64,
+    /// Running guest virtual machines (included in user and nice)
+    pub guest: f64,
+}
+
+/// CPU time breakdown and scheduler activity between two samples
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CpuStats {
+    /// Breakdown across all cores
+    pub total: CpuTimeBreakdown,
+    /// Breakdown per core, in the same order as `SystemStats::cpu_usage`
+    pub cores: Vec<CpuTimeBreakdown>,
+    /// Context switches per second
+    pub context_switches_per_sec: f64,
+    /// Interrupts per second
+    pub interrupts_per_sec: f64,
+    /// Processes and threads created per second
+    pub forks_per_sec: f64,
+}
+
+/// Live clock of one logical CPU
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CpuFrequency {
+    /// Logical CPU number as used by the kernel (`cpuN`)
+    pub cpu: usize,
+    /// Current frequency in MHz
+    pub current_mhz: u64,
+    /// Lowest supported frequency in MHz
+    pub min_mhz: Option<u64>,
+    /// Highest supported frequency in MHz
+    pub max_mhz: Option<u64>,
+    /// Frequency scaling governor, e.g. "powersave"
+    pub governor: Option<String>,
+}
+
+/// Topology of one logical CPU
+#[derive(Serialize, Clone, Debug)]
+pub struct CpuCoreInfo {
+    /// Logical CPU number as used by the kernel (`cpuN`)
+    pub cpu: usize,
+    /// Physical package (socket) the CPU belongs to
+    pub package: Option<u32>,
+    /// Core within the package; hyperthread siblings share it
+    pub core_id: Option<u32>,
+    /// "performance" or "efficiency" on hybrid CPUs
+    pub core_type: Option<String>,
+}
+
+/// One level of the CPU cache hierarchy
+#[derive(Serialize, Clone, Debug)]
+pub struct CpuCache {
+    /// Cache level (1, 2, 3...)
+    pub level: u32,
+    /// "Data", "Instruction" or "Unified"
+    pub cache_type: String,
+    /// Size of a single instance in bytes
+    pub size_bytes: u64,
+    /// Number of instances of this cache in the system
+    pub instances: usize,
+}
+
+/// Logical CPUs attached to a NUMA node
+#[derive(Serialize, Clone, Debug)]
+pub struct NumaNodeCpus {
+    /// NUMA node index
+    pub node: usize,
+    /// Logical CPU indices
+    pub cpus: Vec<usize>,
+}
+
+/// Memory, CPUs and allocation counters of a NUMA node
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NumaNode {
+    /// NUMA node index
+    pub node: usize,
+    /// Logical CPU indices attached to the node
+    pub cpus: Vec<usize>,
+    /// Memory on the node in bytes
+    pub memory_total: u64,
+    /// Free memory on the node in bytes
+    pub memory_free: u64,
+    /// Used memory on the node in bytes
+    pub memory_used: u64,
+    /// Relative access distance to every node, indexed by node
+    pub distances: Vec<u32>,
+    /// Allocations satisfied on the intended node
+    pub numa_hit: u64,
+    /// Allocations that fell back to this node from another one
+    pub numa_miss: u64,
+    /// Allocations on this node by processes running on it
+    pub local_node: u64,
+    /// Allocations on this node by processes running elsewhere
+    pub other_node: u64,
+}
+
+/// Memory a process has on one NUMA node
+#[derive(Serialize, Clone, Debug)]
+pub struct NumaNodeMemory {
+    /// NUMA node index
+    pub node: usize,
+    /// Resident bytes on the node
+    pub bytes: u64,
+}
+
+/// Distribution of a process's memory across NUMA nodes
+#[derive(Serialize, Clone, Debug)]
+pub struct ProcessNumaMemory {
+    /// Identity of the process
+    pub key: ProcessKey,
+    /// Resident memory per node, sorted by node
+    pub nodes: Vec<NumaNodeMemory>,
+}
+
+/// Memory accounting of a single process from `smaps_rollup`
+/// All values are in bytes
+#[derive(Serialize, Clone, Debug)]
+pub struct ProcessMemoryDetails {
+    /// Identity of the process
+    pub key: ProcessKey,
+    /// Resident set size
+    pub rss: u64,
+    /// Proportional set size: resident memory with shared pages divided among their users
+    pub pss: u64,
+    /// Anonymous part of the PSS
+    pub pss_anon: u64,
+    /// File backed part of the PSS
+    pub pss_file: u64,
+    /// Shared memory part of the PSS
+    pub pss_shmem: u64,
+    /// Resident pages shared with other processes
+    pub shared: u64,
+    /// Resident pages used only by this process
+    pub private: u64,
+    /// Memory swapped out
+    pub swap: u64,
+    /// Anonymous memory backed by transparent huge pages
+    pub anon_hugepages: u64,
+    /// Shared memory mapped with huge page table entries
+    pub shmem_hugepages: u64,
+    /// Files mapped with huge page table entries
+    pub file_hugepages: u64,
+}
+
+/// Static description of the processor
+#[derive(Serialize, Clone, Debug)]
+pub struct CpuInfo {
+    /// Marketing model name
+    pub model_name: String,
+    /// Vendor identifier, e.g. "GenuineIntel"
+    pub vendor: String,
+    /// Number of physical packages (sockets)
+    pub packages: usize,
+    /// Number of physical cores
+    pub physical_cores: usize,
+    /// Number of logical CPUs (hardware threads)
+    pub logical_cpus: usize,
+    /// Cache hierarchy
+    pub caches: Vec<CpuCache>,
+    /// CPU to NUMA node mapping
+    pub numa_nodes: Vec<NumaNodeCpus>,
+    /// CPU feature flags
+    pub flags: Vec<String>,
+    /// Topology of each logical CPU
+    pub cores: Vec<CpuCoreInfo>,
+}
+
+/// Share of wall time tasks were stalled, in percent
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PressureAverages {
+    /// Average over the last 10 seconds
+    pub avg10: f64,
+    /// Average over the last 60 seconds
+    pub avg60: f64,
+    /// Average over the last 300 seconds
+    pub avg300: f64,
+    /// Total stall time in microseconds
+    pub total_us: u64,
+}
+
+/// Pressure on a single resource
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PressureResource {
+    /// At least one task was stalled
+    pub some: PressureAverages,
+    /// All non-idle tasks were stalled at once; absent for system-wide CPU
+    /// pressure on older kernels
+    pub full: Option<PressureAverages>,
+}
+
+/// Pressure Stall Information for CPU, memory and I/O
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PressureStats {
+    /// CPU pressure
+    pub cpu: Option<PressureResource>,
+    /// Memory pressure
+    pub memory: Option<PressureResource>,
+    /// I/O pressure
+    pub io: Option<PressureResource>,
+}
+
+/// Pressure Stall Information of the cgroup a process belongs to
+#[derive(Serialize, Clone, Debug)]
+pub struct ProcessCgroupPressure {
+    /// Identity of the process
+    pub key: ProcessKey,
+    /// Path of the cgroup relative to the cgroup v2 root, e.g. `/user.slice`
+    pub cgroup: String,
+    /// Pressure of the cgroup
+    pub pressure: PressureStats,
+}
+
+/// Quantity measured by a hardware sensor
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SensorKind {
+    /// Degrees Celsius
+    Temperature,
+    /// Revolutions per minute
+    Fan,
+    /// Volts
+    Voltage,
+    /// Amperes
+    Current,
+    /// Watts
+    Power,
+}
+
+/// How a sensor reading compares with the thresholds reported by its driver
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SensorStatus {
+    /// Below every threshold, or no thresholds are reported
+    Normal,
+    /// At or above the high threshold; for thermal zones this is the
+    /// passive trip point, where the kernel starts throttling
+    High,
+    /// At or above the critical threshold
+    Critical,
+}
+
+/// A single hardware sensor reading
+#[derive(Serialize, Clone, Debug)]
+pub struct Sensor {
+    /// Chip or thermal zone the sensor belongs to, e.g. "coretemp" or "nvme"
+    pub device: String,
+    /// Sensor label, e.g. "Package id 0" or "Core 3"
+    pub label: String,
+    /// Measured quantity, which also determines the unit
+    pub kind: SensorKind,
+    /// Current reading
+    pub value: f64,
+    /// High threshold reported by the driver
+    pub max: Option<f64>,
+    /// Critical threshold reported by the driver
+    pub critical: Option<f64>,
+    /// Whether the reading has reached one of the thresholds
+    pub status: SensorStatus,
+}
+
+/// Status of a single battery
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BatteryInfo {
+    /// Power supply name, e.g. "BAT0"
+    pub name: String,
+    /// Charge level in percent
+    pub charge_percent: Option<f64>,
+    /// "charging", "discharging", "full", "not charging" or "unknown"
+    pub state: String,
+    /// Current charge or discharge rate in watts
+    pub power_watts: Option<f64>,
+    /// Estimated seconds until empty, while discharging
+    pub time_to_empty_secs: Option<u64>,
+    /// Estimated seconds until full, while charging
+    pub time_to_full_secs: Option<u64>,
+    /// Full capacity relative to the design capacity, in percent
+    pub health_percent: Option<f64>,
+    /// Remaining energy in watt-hours
+    pub energy_now_wh: Option<f64>,
+    /// Energy when fully charged in watt-hours
+    pub energy_full_wh: Option<f64>,
+    /// Energy when fully charged as designed in watt-hours
+    pub energy_full_design_wh: Option<f64>,
+    /// Number of charge cycles, where reported
+    pub cycle_count: Option<u64>,
+}
+
+/// Battery and AC adapter status
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PowerStats {
+    /// System batteries
+    pub batteries: Vec<BatteryInfo>,
+    /// Whether an AC adapter is connected, if the machine reports one
+    pub ac_online: Option<bool>,
+}
+
+/// Description of the host and its operating system
+#[derive(Serialize, Clone, Debug)]
+pub struct SystemInfo {
+    /// Kernel version, e.g. "6.8.0-45-generic"
+    pub kernel_version: Option<String>,
+    /// Human readable distribution name from os-release
+    pub os_name: Option<String>,
+    /// Distribution identifier, e.g. "ubuntu"
+    pub os_id: Option<String>,
+    /// Distribution version, e.g. "24.04"
+    pub os_version: Option<String>,
+    /// Host name
+    pub hostname: Option<String>,
+    /// Boot time as a Unix timestamp
+    pub boot_time: u64,
+    /// CPU architecture, e.g. "x86_64"
+    pub architecture: String,
+    /// Hypervisor, e.g. "kvm" or "vmware"; "unknown" if one is present but
+    /// not recognised
+    pub virtualization: Option<String>,
+    /// Container runtime, e.g. "docker" or "wsl"
+    pub container: Option<String>,
+    /// Init system running as PID 1, e.g. "systemd"
+    pub init_system: Option<String>,
+    /// Number of processes
+    pub process_count: usize,
+    /// Number of threads across all processes
+    pub thread_count: Option<usize>,
+    /// Number of distinct users with a login session
+    pub logged_in_users: usize,
+}
+
+/// Condensed system metrics at one point in time, as kept in the history
+/// Rates are per second; downsampled samples hold the averages of their bucket
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MetricsSample {
+    /// Start of the sample in milliseconds since the Unix epoch
+    pub timestamp: u64,
+    /// CPU usage per core as percentage (0-100)
+    pub cpu_usage: Vec<f32>,
+    /// Used physical memory in bytes
+    pub memory_used: u64,
+    /// Used swap space in bytes
+    pub swap_used: u64,
+    /// Bytes received per second across all interfaces
+    pub network_rx_bytes: u64,
+    /// Bytes transmitted per second across all interfaces
+    pub network_tx_bytes: u64,
+    /// Bytes read per second across all block devices
+    pub disk_read_bytes: u64,
+    /// Bytes written per second across all block devices
+    pub disk_write_bytes: u64,
+}
+
+/// Metrics history over a time range
+#[derive(Serialize, Clone, Debug)]
+pub struct MetricsRange {
+    /// Time covered by each sample in milliseconds
+    pub resolution_ms: u64,
+    /// Samples ordered by time
+    pub samples: Vec<MetricsSample>,
+}
+
+/// Settings of the on-disk metrics recorder
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecorderConfig {
+    /// Whether samples are written to disk
+    pub enabled: bool,
+    /// Minimum time between two recorded samples in seconds
+    pub interval_secs: u64,
+    /// Number of processes recorded per sample, busiest first
+    pub top_processes: usize,
+    /// Number of days, including today, for which recordings are kept
+    pub retention_days: u64,
+    /// Maximum total size of the recordings in bytes
+    pub max_bytes: u64,
+}
+
+impl Default for RecorderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 10,
+            top_processes: 10,
+            retention_days: 7,
+            max_bytes: 256 * 1024 * 1024,
+        }
+    }
+}
+
+/// Settings, storage location and health of the metrics recorder
+#[derive(Serialize, Clone, Debug)]
+pub struct RecorderStatus {
+    /// Current settings
+    pub config: RecorderConfig,
+    /// Directory holding the recordings, absent until the app has started
+    pub directory: Option<String>,
+    /// Total size of the recordings in bytes
+    pub size_bytes: u64,
+    /// Error of the last failed write, if the latest write failed
+    pub last_error: Option<String>,
+}
+
+/// A process as stored in a recorded sample
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RecordedProcess {
+    /// Process ID
+    pub pid: u32,
+    /// Process name
+    pub name: String,
+    /// Username of the process owner
+    pub user: String,
+    /// CPU usage as percentage (0-100)
+    pub cpu_usage: f32,
+    /// Physical memory usage in bytes
+    pub memory_usage: u64,
+}
+
+/// A sample written to disk by the metrics recorder
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RecordedSample {
+    /// Condensed system metrics
+    pub metrics: MetricsSample,
+    /// Load averages for 1, 5, and 15 minutes
+    pub load_avg: [f64; 3],
+    /// The busiest processes at the time of the sample
+    pub top_processes: Vec<RecordedProcess>,
+}
+
+/// Playback state of a replayed capture
+#[derive(Serialize, Clone, Debug)]
+pub struct ReplayStatus {
+    /// Path of the capture file
+    pub path: String,
+    /// Host the capture was taken on
+    pub hostname: Option<String>,
+    /// Time of the first snapshot in milliseconds since the Unix epoch
+    pub start_time: u64,
+    /// Time of the last snapshot in milliseconds since the Unix epoch
+    pub end_time: u64,
+    /// Capture time currently shown in milliseconds since the Unix epoch
+    pub position: u64,
+    /// Whether the position is advancing
+    pub playing: bool,
+    /// Playback speed, where 1.0 is real time
+    pub speed: f64,
+    /// Number of snapshots in the capture
+    pub frame_count: usize,
+}
+
+/// Playback control for a replayed capture
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum ReplayControl {
+    /// Advance the position in real time multiplied by the speed
+    Play,
+    /// Stop advancing the position
+    Pause,
+    /// Jump to a capture time in milliseconds since the Unix epoch
+    Seek { timestamp: u64 },
+    /// Change the playback speed, between 0.1 and 100
+    Speed { speed: f64 },
+}
+
 /// System-wide statistics exposed to the frontend
 /// Provides overall system resource usage and performance metrics
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct SystemStats {
     /// CPU usage per core as percentage (0-100)
     pub cpu_usage: Vec<f32>,
+    /// CPU time breakdown, absent on the first sample and on non-Linux systems
+    pub cpu_times: Option<CpuStats>,
+    /// Current frequency and governor per core
+    pub cpu_frequencies: Vec<CpuFrequency>,
     /// Total physical memory in bytes
     pub memory_total: u64,
     /// Used physical memory in bytes
     pub memory_used: u64,
     /// Free physical memory in bytes
     pub memory_free: u64,
-    /// Cached memory in bytes
+    /// Reclaimable cache memory in bytes
     pub memory_cached: u64,
+    /// Detailed memory breakdown, where the platform exposes one
+    pub memory_breakdown: Option<MemoryBreakdown>,
+    /// Per-node memory and CPUs; a single entry on non-NUMA machines
+    pub numa_nodes: Vec<NumaNode>,
+    /// Swap usage and activity
+    pub swap: SwapStats,
     /// System uptime in seconds
     pub uptime: u64,
     /// Load averages for 1, 5, and 15 minutes
     pub load_avg: [f64; 3],
+    /// Pressure Stall Information, absent where the kernel does not provide it
+    pub pressure: Option<PressureStats>,
     /// Total bytes received over network
     pub network_rx_bytes: u64,
     /// Total bytes transmitted over network
     pub network_tx_bytes: u64,
+    /// Bytes received per second over the filtered interfaces
+    pub filtered_network_rx_bytes: u64,
+    /// Bytes transmitted per second over the filtered interfaces
+    pub filtered_network_tx_bytes: u64,
+    /// Per-interface network statistics, after filtering
+    pub network_interfaces: Vec<NetworkInterface>,
     /// Total disk space in bytes
     pub disk_total_bytes: u64,
     /// Used disk space in bytes
     pub disk_used_bytes: u64,
     /// Free disk space in bytes
     pub disk_free_bytes: u64,
+    /// Mounted filesystems, after filtering
+    pub mounts: Vec<MountInfo>,
+    /// Block device I/O activity
+    pub block_devices: Vec<BlockDeviceStats>,
+    /// Battery status, absent on machines without a battery
+    pub power: Option<PowerStats>,
+}
+
+/// Kind of process lifecycle transition
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessEventKind {
+    /// A new process was created
+    Fork,
+    /// A process replaced its image via exec
+    Exec,
+    /// A process terminated
+    Exit,
+}
+
+/// Mechanism that observed a process lifecycle event
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessEventSource {
+    /// Linux netlink proc connector (sees every process, however short-lived)
+    Netlink,
+    /// Difference between two consecutive process list refreshes
+    Polling,
+}
+
+/// Process lifecycle event exposed to the frontend
+/// Emitted live as `process-activity` and kept in a bounded history
+#[derive(Serialize, Clone, Debug)]
+pub struct ProcessEvent {
+    /// Type of lifecycle transition
+    pub kind: ProcessEventKind,
+    /// Process ID the event refers to
+    pub pid: u32,
+    /// Parent process ID, if known
+    pub ppid: Option<u32>,
+    /// Process name at the time of the event, if it could be resolved
+    pub name: Option<String>,
+    /// Time the event was observed (milliseconds since UNIX epoch)
+    pub timestamp: u64,
+    /// Exit code for normally terminated processes
+    pub exit_code: Option<i32>,
+    /// Signal number for processes terminated by a signal
+    pub exit_signal: Option<i32>,
+    /// Mechanism that observed the event
+    pub source: ProcessEventSource,
 }
@@ -3,10 +3,24 @@
 //! This module handles the global application state, including system monitoring
 //! and process tracking capabilities.
 
-use crate::monitoring::{ProcessMonitor, SystemMonitor};
+use crate::monitoring::{
+    EnvironmentInspector, MetricsRecorder, ProcessActions, ProcessInfo, ProcessLifecycleMonitor,
+    ProcessMonitor, SessionCapture, SystemMonitor, SystemStats,
+};
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use sysinfo::{System, SystemExt};
 
+/// How often the background sampler collects statistics
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Snapshots younger than this are reused instead of collected again, so
+/// CPU usage is never computed over a near-zero interval
+const MIN_COLLECTION_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A collected process list and system statistics with their collection time
+type Snapshot = (Instant, Vec<ProcessInfo>, SystemStats);
+
 /// Global application state
 ///
 /// Maintains thread-safe access to system information and monitoring components
@@ -18,6 +32,24 @@ pub struct AppState {
     pub process_monitor: Mutex<ProcessMonitor>,
     /// System statistics monitoring component
     pub system_monitor: Mutex<SystemMonitor>,
+    /// Process lifecycle (fork/exec/exit) tracking component
+    pub lifecycle_monitor: Mutex<ProcessLifecycleMonitor>,
+    /// Handles to processes selected in the UI, used for signalling
+    pub process_actions: Mutex<ProcessActions>,
+    /// On-demand process environment reader with secret masking
+    pub environment_inspector: Mutex<EnvironmentInspector>,
+    /// Optional on-disk recorder of sampled statistics
+    pub recorder: Mutex<MetricsRecorder>,
+    /// Full session capture and replay
+    pub capture: Mutex<SessionCapture>,
+    /// The last collected snapshot; its lock serializes collections
+    latest: Mutex<Option<Snapshot>>,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AppState {
@@ -34,8 +66,56 @@ impl AppState {
 
         Self {
             process_monitor: Mutex::new(ProcessMonitor::new()),
-            system_monitor: Mutex::new(SystemMonitor::new(&sys)),
+            system_monitor: Mutex::new(SystemMonitor::new()),
+            lifecycle_monitor: Mutex::new(ProcessLifecycleMonitor::new()),
+            process_actions: Mutex::new(ProcessActions::new()),
+            environment_inspector: Mutex::new(EnvironmentInspector::new()),
+            recorder: Mutex::new(MetricsRecorder::new()),
+            capture: Mutex::new(SessionCapture::new()),
+            latest: Mutex::new(None),
             sys: Mutex::new(sys),
         }
     }
+
+    /// Collects the current process list and system statistics
+    ///
+    /// Called both by the frontend and by the background sampler, so the
+    /// metrics history and the recorder keep filling while no window is
+    /// polling. Every collection is added to the history, the recorder and a
+    /// running capture. A snapshot collected less than half a second ago is
+    /// returned again instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error string if:
+    /// * Failed to acquire locks on system state
+    /// * Failed to collect process information
+    pub fn collect(&self) -> Result<(Vec<ProcessInfo>, SystemStats), String> {
+        let mut latest = self.latest.lock().map_err(|e| e.to_string())?;
+        if let Some((collected, processes, stats)) = latest.as_ref() {
+            if collected.elapsed() < MIN_COLLECTION_INTERVAL {
+                return Ok((processes.clone(), stats.clone()));
+            }
+        }
+
+        let mut capture = self.capture.lock().map_err(|e| e.to_string())?;
+        let mut sys = self.sys.lock().map_err(|e| e.to_string())?;
+        sys.refresh_all();
+        sys.refresh_networks_list();
+        sys.refresh_disks_list();
+
+        let mut process_monitor = self.process_monitor.lock().map_err(|e| e.to_string())?;
+        let mut system_monitor = self.system_monitor.lock().map_err(|e| e.to_string())?;
+        let mut lifecycle_monitor = self.lifecycle_monitor.lock().map_err(|e| e.to_string())?;
+        let mut recorder = self.recorder.lock().map_err(|e| e.to_string())?;
+
+        lifecycle_monitor.observe(&sys);
+        let processes = process_monitor.collect_processes(&sys)?;
+        let system_stats = system_monitor.collect_stats(&sys);
+        recorder.record(&system_stats, &processes);
+        capture.write_frame(&processes, &system_stats);
+
+        *latest = Some((Instant::now(), processes.clone(), system_stats.clone()));
+        Ok((processes, system_stats))
+    }
 }
@@ -4,3 +4,5 @@ export { default as StatusFilter } from "./StatusFilter.svelte";
 export { default as PaginationControls } from "./PaginationControls.svelte";
 export { default as ColumnToggle } from "./ColumnToggle.svelte";
 export { default as RefreshControls } from "./RefreshControls.svelte";
+export { default as CaptureControls } from "./CaptureControls.svelte";
+export { default as ReplayBar } from "./ReplayBar.svelte";
@@ -24,6 +24,15 @@ export const REFRESH_RATE_OPTIONS = [
   { value: 30000, label: "30s" },
 ];
 
+export const REPLAY_SPEED_OPTIONS = [
+  { value: 0.5, label: "0.5x" },
+  { value: 1, label: "1x" },
+  { value: 2, label: "2x" },
+  { value: 5, label: "5x" },
+  { value: 10, label: "10x" },
+  { value: 60, label: "60x" },
+];
+
 export const STATUS_OPTIONS = [
   { value: "all", label: "All Statuses" },
   { value: "running", label: "Running" },
@@ -14,7 +14,6 @@ export const DEFAULT_CONFIG: AppConfig = {
       ppid: false,
       root: false,
       command: false,
-      environ: false,
       session_id: false,
       start_time: false,
       run_time: true,
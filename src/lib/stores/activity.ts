@@ -0,0 +1,41 @@
+import { writable } from "svelte/store";
+import type { ProcessEvent } from "$lib/types";
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+const MAX_EVENTS = 200;
+
+function createActivityStore() {
+  const { subscribe, set, update } = writable<ProcessEvent[]>([]);
+  let unlisten: UnlistenFn | null = null;
+
+  const start = async () => {
+    if (unlisten) return;
+    try {
+      set(
+        await invoke<ProcessEvent[]>("get_process_events", {
+          limit: MAX_EVENTS,
+        }),
+      );
+    } catch (e) {
+      console.error("Failed to load process activity:", e);
+    }
+    unlisten = await listen<ProcessEvent[]>("process-activity", (event) => {
+      update((events) => [...events, ...event.payload].slice(-MAX_EVENTS));
+    });
+  };
+
+  const stop = () => {
+    unlisten?.();
+    unlisten = null;
+  };
+
+  return {
+    subscribe,
+    start,
+    stop,
+    clear: () => set([]),
+  };
+}
+
+export const activityStore = createActivityStore();
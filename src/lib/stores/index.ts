@@ -1,3 +1,5 @@
 export * from "./processes";
 export * from "./theme";
 export * from "./settings";
+export * from "./activity";
+export * from "./replay";
@@ -1,5 +1,10 @@
-import { writable, derived } from "svelte/store";
-import type { Process, SystemStats } from "$lib/types";
+import { writable, derived, get } from "svelte/store";
+import type {
+  Process,
+  ProcessKey,
+  ProcessSignal,
+  SystemStats,
+} from "$lib/types";
 import { invoke } from "@tauri-apps/api/core";
 
 interface ProcessStore {
@@ -16,7 +21,7 @@ interface ProcessStore {
   processToKill: Process | null;
   isKilling: boolean;
   isFrozen: boolean;
-  selectedProcessPid: number | null;
+  selectedProcessKey: ProcessKey | null;
   sortConfig: {
     field: keyof Process;
     direction: "asc" | "desc";
@@ -37,13 +42,21 @@ const initialState: ProcessStore = {
   processToKill: null,
   isKilling: false,
   isFrozen: false,
-  selectedProcessPid: null,
+  selectedProcessKey: null,
   sortConfig: {
     field: "cpu_usage",
     direction: "desc",
   },
 };
 
+export const processKey = (process: Process): ProcessKey => ({
+  pid: process.pid,
+  start_time: process.start_time,
+});
+
+const isSameProcess = (process: Process, key: ProcessKey) =>
+  process.pid === key.pid && process.start_time === key.start_time;
+
 function createProcessStore() {
   const { subscribe, set, update } = writable<ProcessStore>(initialState);
 
@@ -56,9 +69,10 @@ function createProcessStore() {
       const result = await invoke<[Process[], SystemStats]>("get_processes");
       update((state) => {
         let updatedSelectedProcess = state.selectedProcess;
-        if (state.selectedProcessPid) {
+        const selectedKey = state.selectedProcessKey;
+        if (selectedKey) {
           updatedSelectedProcess =
-            result[0].find((p) => p.pid === state.selectedProcessPid) || null;
+            result[0].find((p) => isSameProcess(p, selectedKey)) || null;
         }
 
         return {
@@ -77,10 +91,12 @@ function createProcessStore() {
     }
   };
 
-  const killProcess = async (pid: number) => {
+  const killProcess = async (process: Process) => {
     try {
       update((state) => ({ ...state, isKilling: true }));
-      const success = await invoke<boolean>("kill_process", { pid });
+      const success = await invoke<boolean>("kill_process", {
+        key: processKey(process),
+      });
       if (success) {
         await getProcesses();
       } else {
@@ -96,6 +112,50 @@ function createProcessStore() {
     }
   };
 
+  const sendSignal = async (process: Process, signal: ProcessSignal) => {
+    try {
+      await invoke("send_signal", { key: processKey(process), signal });
+    } catch (e: unknown) {
+      update((state) => ({
+        ...state,
+        error: e instanceof Error ? e.message : String(e),
+      }));
+    }
+  };
+
+  // Number of open views (details, kill confirmation) holding each selection
+  const selections = new Map<string, number>();
+  const selectionId = (key: ProcessKey) => `${key.pid}:${key.start_time}`;
+
+  // Pins the process on the backend (a pidfd on Linux) so later actions
+  // cannot reach a different process that reuses the PID
+  const selectProcess = (process: Process) => {
+    const key = processKey(process);
+    const id = selectionId(key);
+    const count = selections.get(id) ?? 0;
+    selections.set(id, count + 1);
+    if (count > 0) return;
+    invoke("select_process", { key }).catch((e) =>
+      console.error("Failed to select process:", e),
+    );
+  };
+
+  // Drops the backend handle once no open view holds the selection
+  const releaseProcess = (key: ProcessKey | null) => {
+    if (!key) return;
+    const id = selectionId(key);
+    const count = selections.get(id) ?? 0;
+    if (count > 1) {
+      selections.set(id, count - 1);
+      return;
+    }
+    selections.delete(id);
+    if (count === 0) return;
+    invoke("release_process", { key }).catch((e) =>
+      console.error("Failed to release process:", e),
+    );
+  };
+
   const toggleSort = (field: keyof Process) => {
     update((state) => ({
       ...state,
@@ -133,24 +193,30 @@ function createProcessStore() {
     update((state) => ({ ...state, currentPage }));
 
   const showProcessDetails = (process: Process) => {
+    const previousKey = get({ subscribe }).selectedProcessKey;
+    selectProcess(process);
+    releaseProcess(previousKey);
     update((state) => ({
       ...state,
-      selectedProcessPid: process.pid,
+      selectedProcessKey: processKey(process),
       selectedProcess: process,
       showInfoModal: true,
     }));
   };
 
   const closeProcessDetails = () => {
+    const { selectedProcessKey } = get({ subscribe });
     update((state) => ({
       ...state,
       showInfoModal: false,
       selectedProcess: null,
-      selectedProcessPid: null,
+      selectedProcessKey: null,
     }));
+    releaseProcess(selectedProcessKey);
   };
 
   const confirmKillProcess = (process: Process) => {
+    selectProcess(process);
     update((state) => ({
       ...state,
       processToKill: process,
@@ -159,38 +225,30 @@ function createProcessStore() {
   };
 
   const closeConfirmKill = () => {
+    const { processToKill } = get({ subscribe });
     update((state) => ({
       ...state,
       showConfirmModal: false,
       processToKill: null,
     }));
+    if (processToKill) releaseProcess(processKey(processToKill));
   };
 
   const handleConfirmKill = async () => {
-    let processToKill: Process | null = null;
-
-    let currentState: ProcessStore | undefined;
-    const unsubscribe = subscribe((state) => {
-      currentState = state;
-    });
-    unsubscribe();
-
-    if (currentState?.processToKill && "pid" in currentState.processToKill) {
-      processToKill = currentState.processToKill;
-    }
-
+    const { processToKill } = get({ subscribe });
     if (!processToKill?.pid) {
       return;
     }
 
     try {
-      await killProcess(processToKill.pid);
+      await killProcess(processToKill);
     } finally {
       update((state) => ({
         ...state,
         showConfirmModal: false,
         processToKill: null,
       }));
+      releaseProcess(processKey(processToKill));
     }
   };
 
@@ -202,6 +260,7 @@ function createProcessStore() {
     setIsLoading,
     getProcesses,
     killProcess,
+    sendSignal,
     toggleSort,
     togglePin,
     setSearchTerm,
@@ -0,0 +1,111 @@
+import { writable } from "svelte/store";
+import type { ReplayControl, ReplayStatus } from "$lib/types";
+import { invoke } from "@tauri-apps/api/core";
+import { processStore } from "./processes";
+
+const STATUS_INTERVAL = 500;
+
+interface ReplayStore {
+  status: ReplayStatus | null;
+  capturePath: string | null;
+  error: string | null;
+}
+
+const initialState: ReplayStore = {
+  status: null,
+  capturePath: null,
+  error: null,
+};
+
+function createReplayStore() {
+  const { subscribe, update } = writable<ReplayStore>(initialState);
+  let statusInterval: number | null = null;
+
+  const setError = (e: unknown) =>
+    update((state) => ({
+      ...state,
+      error: e instanceof Error ? e.message : String(e),
+    }));
+
+  // The replay position moves while playing, so it is polled while open
+  const setStatus = (status: ReplayStatus | null) => {
+    update((state) => ({ ...state, status, error: null }));
+    if (status && !statusInterval) {
+      statusInterval = setInterval(refresh, STATUS_INTERVAL);
+    } else if (!status && statusInterval) {
+      clearInterval(statusInterval);
+      statusInterval = null;
+    }
+  };
+
+  const refresh = async () => {
+    try {
+      setStatus(await invoke<ReplayStatus | null>("get_replay_status"));
+    } catch (e) {
+      setError(e);
+    }
+  };
+
+  const startCapture = async (path: string) => {
+    try {
+      await invoke("start_capture", { path });
+      update((state) => ({ ...state, capturePath: path, error: null }));
+    } catch (e) {
+      setError(e);
+    }
+  };
+
+  const stopCapture = async () => {
+    try {
+      await invoke<number>("stop_capture");
+      update((state) => ({ ...state, error: null }));
+    } catch (e) {
+      setError(e);
+    } finally {
+      update((state) => ({ ...state, capturePath: null }));
+    }
+  };
+
+  const openReplay = async (path: string) => {
+    try {
+      setStatus(await invoke<ReplayStatus>("open_replay", { path }));
+      await processStore.getProcesses();
+    } catch (e) {
+      setError(e);
+    }
+  };
+
+  const closeReplay = async () => {
+    try {
+      await invoke("close_replay");
+      setStatus(null);
+      await processStore.getProcesses();
+    } catch (e) {
+      setError(e);
+    }
+  };
+
+  const control = async (control: ReplayControl) => {
+    try {
+      setStatus(await invoke<ReplayStatus>("control_replay", { control }));
+      await processStore.getProcesses();
+    } catch (e) {
+      setError(e);
+    }
+  };
+
+  return {
+    subscribe,
+    refresh,
+    startCapture,
+    stopCapture,
+    openReplay,
+    closeReplay,
+    play: () => control({ action: "play" }),
+    pause: () => control({ action: "pause" }),
+    seek: (timestamp: number) => control({ action: "seek", timestamp }),
+    setSpeed: (speed: number) => control({ action: "speed", speed }),
+  };
+}
+
+export const replayStore = createReplayStore();
@@ -8,8 +8,8 @@ export interface Process {
   status: string;
   user: string;
   command: string;
+  executable: string;
   threads?: number;
-  environ: string[];
   root: string;
   virtual_memory: number;
   start_time: number;
@@ -18,21 +18,502 @@ export interface Process {
   session_id?: number;
 }
 
+export interface ProcessKey {
+  pid: number;
+  start_time: number;
+}
+
+export type ProcessSignal =
+  | "HUP"
+  | "INT"
+  | "QUIT"
+  | "KILL"
+  | "TERM"
+  | "STOP"
+  | "CONT"
+  | "USR1"
+  | "USR2";
+
+export interface EnvironmentVariable {
+  key: string;
+  value: string;
+  masked: boolean;
+}
+
+export interface ProcessEnvironment {
+  key: ProcessKey;
+  variables: EnvironmentVariable[];
+}
+
+export interface EnvironmentDiff {
+  left: ProcessKey;
+  right: ProcessKey;
+  only_left: EnvironmentVariable[];
+  only_right: EnvironmentVariable[];
+  changed: {
+    key: string;
+    left: string;
+    right: string;
+    masked: boolean;
+  }[];
+  unchanged: number;
+}
+
+export interface MemoryBreakdown {
+  available: number;
+  buffers: number;
+  cached: number;
+  shared: number;
+  slab_reclaimable: number;
+  slab_unreclaimable: number;
+  dirty: number;
+  writeback: number;
+  anon: number;
+  mapped: number;
+  hugepages_total: number;
+  hugepages_free: number;
+  hugepage_size: number;
+  hugepage_pools: HugePagePool[];
+  anon_hugepages: number;
+  shmem_hugepages: number;
+  file_hugepages: number;
+  transparent_hugepages: TransparentHugePages | null;
+  committed_as: number;
+  commit_limit: number;
+}
+
+export interface HugePagePool {
+  page_size: number;
+  total: number;
+  free: number;
+  reserved: number;
+  surplus: number;
+}
+
+export interface TransparentHugePages {
+  enabled: string;
+  defrag: string;
+  fault_alloc: number;
+  fault_fallback: number;
+  collapse_alloc: number;
+  split_page: number;
+}
+
+export interface SwapDevice {
+  name: string;
+  kind: string;
+  size: number;
+  used: number;
+  priority: number;
+  zram: {
+    original_size: number;
+    compressed_size: number;
+    memory_used: number;
+    compression_ratio: number;
+  } | null;
+}
+
+export interface SwapStats {
+  total: number;
+  used: number;
+  free: number;
+  devices: SwapDevice[];
+  pages_in_per_sec: number | null;
+  pages_out_per_sec: number | null;
+}
+
+export interface NetworkInterface {
+  name: string;
+  mac_address: string;
+  ip_addresses: string[];
+  link_state: string;
+  mtu: number | null;
+  speed_mbps: number | null;
+  is_virtual: boolean;
+  rx_bytes_per_sec: number | null;
+  tx_bytes_per_sec: number | null;
+  rx_packets_per_sec: number | null;
+  tx_packets_per_sec: number | null;
+  rx_bytes_total: number;
+  tx_bytes_total: number;
+  rx_errors: number;
+  tx_errors: number;
+  rx_dropped: number | null;
+  tx_dropped: number | null;
+}
+
+export interface MountInfo {
+  device: string;
+  mount_point: string;
+  fs_type: string;
+  read_only: boolean;
+  total_bytes: number;
+  used_bytes: number;
+  free_bytes: number;
+  inodes_total: number | null;
+  inodes_used: number | null;
+  inodes_free: number | null;
+}
+
+export interface CpuTimeBreakdown {
+  user: number;
+  nice: number;
+  system: number;
+  idle: number;
+  iowait: number;
+  irq: number;
+  softirq: number;
+  steal: number;
+  guest: number;
+}
+
+export interface CpuStats {
+  total: CpuTimeBreakdown;
+  cores: CpuTimeBreakdown[];
+  context_switches_per_sec: number;
+  interrupts_per_sec: number;
+  forks_per_sec: number;
+}
+
+export interface SystemInfo {
+  kernel_version: string | null;
+  os_name: string | null;
+  os_id: string | null;
+  os_version: string | null;
+  hostname: string | null;
+  boot_time: number;
+  architecture: string;
+  virtualization: string | null;
+  container: string | null;
+  init_system: string | null;
+  process_count: number;
+  thread_count: number | null;
+  logged_in_users: number;
+}
+
+export interface CpuFrequency {
+  cpu: number;
+  current_mhz: number;
+  min_mhz: number | null;
+  max_mhz: number | null;
+  governor: string | null;
+}
+
+export interface CpuCoreInfo {
+  cpu: number;
+  package: number | null;
+  core_id: number | null;
+  core_type: string | null;
+}
+
+export interface CpuCache {
+  level: number;
+  cache_type: string;
+  size_bytes: number;
+  instances: number;
+}
+
+export interface NumaNodeCpus {
+  node: number;
+  cpus: number[];
+}
+
+export interface NumaNode {
+  node: number;
+  cpus: number[];
+  memory_total: number;
+  memory_free: number;
+  memory_used: number;
+  distances: number[];
+  numa_hit: number;
+  numa_miss: number;
+  local_node: number;
+  other_node: number;
+}
+
+export interface NumaNodeMemory {
+  node: number;
+  bytes: number;
+}
+
+export interface ProcessNumaMemory {
+  key: ProcessKey;
+  nodes: NumaNodeMemory[];
+}
+
+export interface ProcessMemoryDetails {
+  key: ProcessKey;
+  rss: number;
+  pss: number;
+  pss_anon: number;
+  pss_file: number;
+  pss_shmem: number;
+  shared: number;
+  private: number;
+  swap: number;
+  anon_hugepages: number;
+  shmem_hugepages: number;
+  file_hugepages: number;
+}
+
+export interface CpuInfo {
+  model_name: string;
+  vendor: string;
+  packages: number;
+  physical_cores: number;
+  logical_cpus: number;
+  caches: CpuCache[];
+  numa_nodes: NumaNodeCpus[];
+  flags: string[];
+  cores: CpuCoreInfo[];
+}
+
+export interface BatteryInfo {
+  name: string;
+  charge_percent: number | null;
+  state: string;
+  power_watts: number | null;
+  time_to_empty_secs: number | null;
+  time_to_full_secs: number | null;
+  health_percent: number | null;
+  energy_now_wh: number | null;
+  energy_full_wh: number | null;
+  energy_full_design_wh: number | null;
+  cycle_count: number | null;
+}
+
+export interface PowerStats {
+  batteries: BatteryInfo[];
+  ac_online: boolean | null;
+}
+
+export type SensorKind = "temperature" | "fan" | "voltage" | "current" | "power";
+
+export type SensorStatus = "normal" | "high" | "critical";
+
+export interface Sensor {
+  device: string;
+  label: string;
+  kind: SensorKind;
+  value: number;
+  max: number | null;
+  critical: number | null;
+  status: SensorStatus;
+}
+
+export interface PressureAverages {
+  avg10: number;
+  avg60: number;
+  avg300: number;
+  total_us: number;
+}
+
+export interface PressureResource {
+  some: PressureAverages;
+  full: PressureAverages | null;
+}
+
+export interface PressureStats {
+  cpu: PressureResource | null;
+  memory: PressureResource | null;
+  io: PressureResource | null;
+}
+
+export interface ProcessCgroupPressure {
+  key: ProcessKey;
+  cgroup: string;
+  pressure: PressureStats;
+}
+
+export interface BlockDeviceStats {
+  name: string;
+  is_virtual: boolean;
+  read_bytes_per_sec: number | null;
+  write_bytes_per_sec: number | null;
+  reads_per_sec: number | null;
+  writes_per_sec: number | null;
+  read_latency_ms: number | null;
+  write_latency_ms: number | null;
+  queue_depth: number | null;
+  utilization_percent: number | null;
+  in_flight: number;
+  partitions: BlockDeviceStats[];
+}
+
+export interface MountFilter {
+  exclude_fs_types: string[];
+  exclude_mount_points: string[];
+}
+
+export interface NetworkFilter {
+  include: string[];
+  exclude: string[];
+  hide_virtual: boolean;
+}
+
 export interface SystemStats {
   cpu_usage: number[];
+  cpu_times: CpuStats | null;
+  cpu_frequencies: CpuFrequency[];
   memory_total: number;
   memory_used: number;
   memory_free: number;
   memory_cached: number;
+  memory_breakdown: MemoryBreakdown | null;
+  numa_nodes: NumaNode[];
+  swap: SwapStats;
   uptime: number;
   load_avg: [number, number, number];
+  pressure: PressureStats | null;
   network_rx_bytes: number;
   network_tx_bytes: number;
+  filtered_network_rx_bytes: number;
+  filtered_network_tx_bytes: number;
+  network_interfaces: NetworkInterface[];
   disk_total_bytes: number;
   disk_used_bytes: number;
   disk_free_bytes: number;
+  mounts: MountInfo[];
+  block_devices: BlockDeviceStats[];
+  power: PowerStats | null;
+}
+
+export interface ProcessEvent {
+  kind: "fork" | "exec" | "exit";
+  pid: number;
+  ppid: number | null;
+  name: string | null;
+  timestamp: number;
+  exit_code: number | null;
+  exit_signal: number | null;
+  source: "netlink" | "polling";
 }
 
+export interface ExitedProcess {
+  pid: number;
+  ppid: number;
+  name: string;
+  command: string;
+  user: string;
+  start_time: number;
+  exit_time: number;
+  lifetime: number;
+  cpu_usage: number;
+  memory_usage: number;
+  peak_cpu_usage: number;
+  peak_memory_usage: number;
+  disk_usage: [number, number]; // [total_read_bytes, total_written_bytes]
+  exit_code: number | null;
+  exit_signal: number | null;
+}
+
+export interface ProcessSample {
+  timestamp: number;
+  cpu_usage: number;
+  memory_usage: number;
+  disk_read_bytes_per_sec: number;
+  disk_written_bytes_per_sec: number;
+}
+
+export interface ProcessHistory {
+  key: ProcessKey;
+  resolution: number;
+  exit_time: number | null;
+  samples: ProcessSample[];
+}
+
+export interface LoginSession {
+  user: string;
+  terminal: string;
+  host: string | null;
+  login_time: number;
+  pid: number;
+  process_count: number;
+  cpu_usage: number;
+  memory_usage: number;
+  processes: ProcessKey[];
+}
+
+export type ProcessGrouping = "user" | "name" | "executable";
+
+export interface ProcessGroup {
+  key: string;
+  process_count: number;
+  cpu_usage: number;
+  memory_usage: number;
+  disk_read_bytes: number;
+  disk_written_bytes: number;
+  members: ProcessKey[];
+}
+
+export interface ProcessGroupDetails {
+  members: Process[];
+  pss: number;
+  pss_skipped: number;
+}
+
+export interface MetricsSample {
+  timestamp: number;
+  cpu_usage: number[];
+  memory_used: number;
+  swap_used: number;
+  network_rx_bytes: number;
+  network_tx_bytes: number;
+  disk_read_bytes: number;
+  disk_write_bytes: number;
+}
+
+export interface MetricsRange {
+  resolution_ms: number;
+  samples: MetricsSample[];
+}
+
+export interface RecorderConfig {
+  enabled: boolean;
+  interval_secs: number;
+  top_processes: number;
+  retention_days: number;
+  max_bytes: number;
+}
+
+export interface RecorderStatus {
+  config: RecorderConfig;
+  directory: string | null;
+  size_bytes: number;
+  last_error: string | null;
+}
+
+export interface RecordedProcess {
+  pid: number;
+  name: string;
+  user: string;
+  cpu_usage: number;
+  memory_usage: number;
+}
+
+export interface RecordedSample {
+  metrics: MetricsSample;
+  load_avg: [number, number, number];
+  top_processes: RecordedProcess[];
+}
+
+export interface ReplayStatus {
+  path: string;
+  hostname: string | null;
+  start_time: number;
+  end_time: number;
+  position: number;
+  playing: boolean;
+  speed: number;
+  frame_count: number;
+}
+
+export type ReplayControl =
+  | { action: "play" }
+  | { action: "pause" }
+  | { action: "seek"; timestamp: number }
+  | { action: "speed"; speed: number };
+
 export interface Column {
   id: keyof Process;
   label: string;